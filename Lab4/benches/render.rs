@@ -0,0 +1,106 @@
+// benches/render.rs
+// Per-planet render timings, without a window: `cargo bench`
+use criterion::{criterion_group, criterion_main, Criterion};
+use raylib::prelude::*;
+use std::f32::consts::PI;
+use ship::camera::Camera;
+use ship::framebuffer::Framebuffer;
+use ship::light::Light;
+use ship::matrix::{create_model_matrix, create_projection_matrix, create_viewport_matrix};
+use ship::obj::Obj;
+use ship::shaders::{generate_craters, planet_axial_tilt, planet_rotation_speed, MoonParams, RingParams};
+use ship::{render_planet, render_scene, DebugView, NoiseQuality, RenderScratch, ShadingMode, Uniforms};
+
+const WIDTH: i32 = 800;
+const HEIGHT: i32 = 600;
+const PLANETS: [&str; 6] = ["rocky", "gaseous", "biolum", "ringed", "ice", "lava"];
+
+// Same view the app starts with: camera at (0, 0, 8) looking at the planet, time 1s
+fn uniforms_for(planet_type: i32, framebuffer: &Framebuffer) -> Uniforms {
+    let camera = Camera::new(
+        Vector3::new(0.0, 0.0, 8.0),
+        Vector3::new(0.0, 0.0, 0.0),
+        Vector3::new(0.0, 1.0, 0.0),
+    );
+    let rotation = Vector3::new(0.0, 0.0, planet_axial_tilt(planet_type).to_radians());
+    Uniforms {
+        model_matrix: create_model_matrix(Vector3::new(0.0, 0.0, 0.0), 1.0, rotation),
+        view_matrix: camera.get_view_matrix(),
+        projection_matrix: create_projection_matrix(PI / 3.0, WIDTH as f32 / HEIGHT as f32, 0.1, 100.0),
+        viewport_matrix: create_viewport_matrix(0.0, 0.0, framebuffer.width as f32, framebuffer.height as f32),
+        camera_position: camera.get_position(),
+        time: 1.0,
+        dt: 0.0,
+        planet_type,
+        render_type: 0,
+        shading_mode: ShadingMode::Procedural,
+        lighting_model: None,
+        debug_view: DebugView::Off,
+        noise_quality: NoiseQuality::Medium,
+        seed: 0,
+        craters: generate_craters(0, 12),
+        ao_strength: 0.5,
+        moon: MoonParams::default(),
+        rings: RingParams::default(),
+        ring_shadow_softness: 0.08,
+        shadow_moons: Vec::new(),
+        eclipse_softness: 0.3,
+        rotation_speed: planet_rotation_speed(planet_type),
+        tone_mapping: false,
+        color_map: None,
+        cull_backfaces: true,
+    }
+}
+
+fn bench_planets(c: &mut Criterion) {
+    let obj = Obj::load(concat!(env!("CARGO_MANIFEST_DIR"), "/models/sphere.obj")).expect("models/sphere.obj");
+    let vertices = obj.get_vertex_array();
+    let lights = Light::preset("key");
+    let moons = [MoonParams::default()];
+
+    let mut framebuffer = Framebuffer::new(WIDTH, HEIGHT);
+    let mut scratch = RenderScratch::default();
+
+    // The planet surface alone
+    let mut group = c.benchmark_group("render_planet");
+    for (planet_type, name) in PLANETS.iter().enumerate() {
+        let uniforms = uniforms_for(planet_type as i32, &framebuffer);
+        group.bench_function(*name, |b| {
+            b.iter(|| {
+                framebuffer.clear();
+                render_planet(&mut framebuffer, &mut scratch, &uniforms, &vertices, &lights);
+            })
+        });
+    }
+    group.finish();
+
+    // The whole frame: adds the clouds, rings or moon that go with each planet
+    let mut group = c.benchmark_group("render_scene");
+    for (planet_type, name) in PLANETS.iter().enumerate() {
+        let uniforms = uniforms_for(planet_type as i32, &framebuffer);
+        group.bench_function(*name, |b| {
+            b.iter(|| {
+                framebuffer.clear();
+                render_scene(&mut framebuffer, &mut scratch, &uniforms, &vertices, &lights, &moons);
+            })
+        });
+    }
+    group.finish();
+
+    // The rocky surface on generated spheres of growing detail (rings, sectors = 2 * rings)
+    let mut group = c.benchmark_group("uv_sphere");
+    let uniforms = uniforms_for(0, &framebuffer);
+    for rings in [8, 32, 128] {
+        let vertices = Obj::uv_sphere(rings, rings * 2).get_vertex_array();
+        group.bench_function(format!("rings_{}", rings), |b| {
+            b.iter(|| {
+                framebuffer.clear();
+                render_planet(&mut framebuffer, &mut scratch, &uniforms, &vertices, &lights);
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_planets);
+criterion_main!(benches);
@@ -0,0 +1,130 @@
+// args.rs
+use std::env;
+
+const DEFAULT_WIDTH: i32 = 1300;
+const DEFAULT_HEIGHT: i32 = 900;
+
+const DEFAULT_OUT: &str = "planet.png";
+
+// Projection clipping planes, as distances from the camera
+const DEFAULT_NEAR: f32 = 0.1;
+const DEFAULT_FAR: f32 = 100.0;
+
+/// Command-line options: `--width <px> --height <px> --planet <0-5> --texture <file.png>
+/// --near <d> --far <d> --sphere <rings>`, plus `--headless [--out <file.png>]` to render
+/// one frame to a PNG without a window
+pub struct Args {
+    pub width: i32,
+    pub height: i32,
+    pub planet: i32,
+    pub headless: bool,
+    pub out: String,
+    pub texture: Option<String>, // color map for the rocky planet's textured mode
+    pub near: f32, // clipping planes, 0 < near < far
+    pub far: f32,
+    pub sphere_rings: Option<u32>, // generate the sphere with this many rings instead of loading sphere.obj
+}
+
+impl Default for Args {
+    fn default() -> Self {
+        Args {
+            width: DEFAULT_WIDTH,
+            height: DEFAULT_HEIGHT,
+            planet: 0,
+            headless: false,
+            out: DEFAULT_OUT.to_string(),
+            texture: None,
+            near: DEFAULT_NEAR,
+            far: DEFAULT_FAR,
+            sphere_rings: None,
+        }
+    }
+}
+
+impl Args {
+    /// Parses the process arguments. Missing or malformed values keep their defaults.
+    pub fn parse() -> Self {
+        Self::parse_from(env::args().skip(1))
+    }
+
+    pub fn parse_from<I: Iterator<Item = String>>(mut args: I) -> Self {
+        let mut parsed = Args::default();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--width" => {
+                    if let Some(value) = parse_positive(&arg, args.next()) {
+                        parsed.width = value;
+                    }
+                }
+                "--height" => {
+                    if let Some(value) = parse_positive(&arg, args.next()) {
+                        parsed.height = value;
+                    }
+                }
+                "--planet" => {
+                    match args.next().and_then(|v| v.parse::<i32>().ok()) {
+                        Some(value) if (0..=5).contains(&value) => parsed.planet = value,
+                        _ => eprintln!("--planet espera un número entre 0 y 5, se usa {}", parsed.planet),
+                    }
+                }
+                "--headless" => parsed.headless = true,
+                "--out" => match args.next() {
+                    Some(path) => parsed.out = path,
+                    None => eprintln!("--out espera una ruta, se usa {}", parsed.out),
+                },
+                "--texture" => match args.next() {
+                    Some(path) => parsed.texture = Some(path),
+                    None => eprintln!("--texture espera la ruta de un PNG"),
+                },
+                "--sphere" => {
+                    if let Some(value) = parse_positive(&arg, args.next()) {
+                        parsed.sphere_rings = Some(value as u32);
+                    }
+                }
+                "--near" => {
+                    if let Some(value) = parse_distance(&arg, args.next()) {
+                        parsed.near = value;
+                    }
+                }
+                "--far" => {
+                    if let Some(value) = parse_distance(&arg, args.next()) {
+                        parsed.far = value;
+                    }
+                }
+                _ => eprintln!("Argumento desconocido: {}", arg),
+            }
+        }
+
+        if parsed.near >= parsed.far {
+            eprintln!(
+                "--near ({}) debe ser menor que --far ({}), se usan {} y {}",
+                parsed.near, parsed.far, DEFAULT_NEAR, DEFAULT_FAR
+            );
+            parsed.near = DEFAULT_NEAR;
+            parsed.far = DEFAULT_FAR;
+        }
+
+        parsed
+    }
+}
+
+fn parse_positive(flag: &str, value: Option<String>) -> Option<i32> {
+    match value.as_deref().map(str::parse::<i32>) {
+        Some(Ok(n)) if n > 0 => Some(n),
+        _ => {
+            eprintln!("{} espera un entero positivo, se usa el valor por defecto", flag);
+            None
+        }
+    }
+}
+
+fn parse_distance(flag: &str, value: Option<String>) -> Option<f32> {
+    match value.as_deref().map(str::parse::<f32>) {
+        Some(Ok(d)) if d.is_finite() && d > 0.0 => Some(d),
+        _ => {
+            eprintln!("{} espera una distancia positiva, se usa el valor por defecto", flag);
+            None
+        }
+    }
+}
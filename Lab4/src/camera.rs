@@ -2,9 +2,22 @@
 #![allow(dead_code)]
 
 use raylib::prelude::*;
-use crate::matrix::create_view_matrix;
+use crate::matrix::create_look_at;
 use std::f32::consts::PI;
 
+// Longest frame time the camera integrates, so a stall doesn't fling it across the scene
+const MAX_CAMERA_DT: f32 = 0.1;
+
+/// Keys held during one frame, as directions in -1..1 per axis
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CameraInput {
+    pub yaw: f32,   // + turns with numpad 4
+    pub pitch: f32, // + raises with numpad 8
+    pub zoom: f32,  // + moves away (numpad -)
+    pub pan: f32,   // + moves the center left (Z)
+    pub lift: f32,  // + moves the center up (Page Up)
+}
+
 pub struct Camera {
     // Camera position/orientation
     pub eye: Vector3,        // Camera position
@@ -17,9 +30,15 @@ pub struct Camera {
     pub distance: f32,       // Distance from target
 
     // Movement speed
-    pub rotation_speed: f32,
-    pub zoom_speed: f32,
-    pub pan_speed: f32,
+    pub rotation_speed: f32,    // Radians per second while a rotation key is held
+    pub move_speed: f32,        // Units per second for keyboard zoom and panning
+    pub zoom_speed: f32,        // Units per mouse wheel notch
+    pub damping: f32,           // How fast velocity follows the keys (1/s); 0 reacts instantly
+    pub mouse_sensitivity: f32, // Radians per pixel of mouse drag
+    pub track_smoothing: f32,   // Fraction of the remaining distance the center moves per track() call
+
+    // Current keyboard velocity: yaw, pitch, zoom, pan, lift (same order as CameraInput)
+    velocity: [f32; 5],
 }
 
 impl Camera {
@@ -42,10 +61,64 @@ impl Camera {
             yaw,
             pitch,
             distance,
-            rotation_speed: 0.05,
+            rotation_speed: 3.0,
+            move_speed: 6.0,
             zoom_speed: 0.5,
-            pan_speed: 0.1,
+            damping: 10.0,
+            mouse_sensitivity: 0.005,
+            track_smoothing: 0.1,
+            velocity: [0.0; 5],
+        }
+    }
+
+    /// Restore the default view: eye at (0, 0, 8) looking at the origin with +Y up.
+    /// Orbit state (yaw, pitch, distance) is recomputed; speeds are kept.
+    pub fn reset(&mut self) {
+        self.set_view(
+            Vector3::new(0.0, 0.0, 8.0),
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+        );
+    }
+
+    /// Moves the camera to `eye` looking at `target`, recomputing the orbit state
+    /// (yaw, pitch, distance) from them and stopping any keyboard motion; speeds are kept.
+    pub fn set_view(&mut self, eye: Vector3, target: Vector3, up: Vector3) {
+        let default = Camera::new(eye, target, up);
+        self.eye = default.eye;
+        self.target = default.target;
+        self.up = default.up;
+        self.yaw = default.yaw;
+        self.pitch = default.pitch;
+        self.distance = default.distance;
+        self.velocity = [0.0; 5];
+    }
+
+    /// Aims at the center of the box `min`..`max` and backs off along the current view
+    /// direction until the box's bounding sphere fits a vertical field of view of `fov`
+    /// radians, with a 20% margin. A zero-size box is framed as if it had radius 1.
+    pub fn frame_bounds(&mut self, min: Vector3, max: Vector3, fov: f32) {
+        let center = Vector3::new((min.x + max.x) * 0.5, (min.y + max.y) * 0.5, (min.z + max.z) * 0.5);
+        let half = Vector3::new((max.x - min.x) * 0.5, (max.y - min.y) * 0.5, (max.z - min.z) * 0.5);
+        let mut radius = (half.x * half.x + half.y * half.y + half.z * half.z).sqrt();
+        if radius.is_nan() || radius < 1e-6 {
+            radius = 1.0;
         }
+        let half_fov = (fov * 0.5).clamp(0.01, PI / 2.0 - 0.01);
+
+        self.target = center;
+        self.distance = (radius / half_fov.sin() * 1.2).max(0.5);
+        self.update_eye_position();
+    }
+
+    /// Follow a moving object: eases the orbit center toward `target` by `track_smoothing`
+    /// and keeps yaw, pitch and distance, so the eye moves along with it. Call once per frame.
+    pub fn track(&mut self, target: Vector3) {
+        let t = self.track_smoothing.clamp(0.0, 1.0);
+        self.target.x += (target.x - self.target.x) * t;
+        self.target.y += (target.y - self.target.y) * t;
+        self.target.z += (target.z - self.target.z) * t;
+        self.update_eye_position();
     }
 
     /// Update camera eye position based on yaw, pitch, and distance
@@ -62,98 +135,154 @@ impl Camera {
         self.eye.z = self.target.z + self.distance * self.pitch.cos() * self.yaw.sin();
     }
 
+    /// Get the camera (eye) position in world space
+    pub fn get_position(&self) -> Vector3 {
+        self.eye
+    }
+
     /// Get the view matrix for this camera
     pub fn get_view_matrix(&self) -> Matrix {
-        create_view_matrix(self.eye, self.target, self.up)
+        create_look_at(self.eye, self.target, self.up)
     }
 
-    /// Process keyboard input to control the camera
+    /// Orbits around the target by the given angles in radians, right away and without
+    /// touching the keyboard velocity (for scripted moves such as a recording turntable)
+    pub fn orbit(&mut self, delta_yaw: f32, delta_pitch: f32) {
+        self.yaw += delta_yaw;
+        self.pitch += delta_pitch;
+        self.update_eye_position();
+    }
+
+    /// Process keyboard and mouse input to control the camera
     pub fn process_input(&mut self, window: &RaylibHandle) {
-        // Rotation controls (yaw)
-        if window.is_key_down(KeyboardKey::KEY_A) {
-            self.yaw += self.rotation_speed;
-            self.update_eye_position();
-        }
-        if window.is_key_down(KeyboardKey::KEY_D) {
-            self.yaw -= self.rotation_speed;
-            self.update_eye_position();
+        // Orbit with left mouse drag: horizontal changes yaw, vertical changes pitch.
+        // update_eye_position clamps pitch so the camera never flips over the poles.
+        if window.is_mouse_button_down(MouseButton::MOUSE_BUTTON_LEFT) {
+            let delta = window.get_mouse_delta();
+            if delta.x != 0.0 || delta.y != 0.0 {
+                self.yaw += delta.x * self.mouse_sensitivity;
+                self.pitch += delta.y * self.mouse_sensitivity;
+                self.update_eye_position();
+            }
         }
 
-        // Rotation controls (pitch)
-        if window.is_key_down(KeyboardKey::KEY_W) {
-            self.pitch += self.rotation_speed;
-            self.update_eye_position();
-        }
-        if window.is_key_down(KeyboardKey::KEY_S) {
-            self.pitch -= self.rotation_speed;
+        // Mouse wheel zoom (distance from target)
+        let wheel = window.get_mouse_wheel_move();
+        if wheel != 0.0 {
+            self.distance = (self.distance - wheel * self.zoom_speed).max(0.5);
             self.update_eye_position();
         }
 
-        // Zoom controls (distance from target) - arrow keys
-        if window.is_key_down(KeyboardKey::KEY_UP) {
-            self.distance -= self.zoom_speed;
-            if self.distance < 0.5 {
-                self.distance = 0.5; // Prevent camera from going too close
+        let key = |positive: &[KeyboardKey], negative: &[KeyboardKey]| {
+            let down = |keys: &[KeyboardKey]| keys.iter().any(|k| window.is_key_down(*k));
+            down(positive) as i32 as f32 - down(negative) as i32 as f32
+        };
+        // The camera used to sit on W/A/S/D, Q/E, R/F and the arrows. Those keys now toggle
+        // the depth view (D), save the scene (S), reset the camera (R), show the overlay (F),
+        // cycle the noise quality (Q) and turn the planet (arrows), so it moved here
+        let input = CameraInput {
+            yaw: key(&[KeyboardKey::KEY_KP_4], &[KeyboardKey::KEY_KP_6]),
+            pitch: key(&[KeyboardKey::KEY_KP_8], &[KeyboardKey::KEY_KP_2]),
+            zoom: key(&[KeyboardKey::KEY_KP_SUBTRACT], &[KeyboardKey::KEY_KP_ADD]),
+            pan: key(&[KeyboardKey::KEY_Z], &[KeyboardKey::KEY_C]),
+            lift: key(&[KeyboardKey::KEY_PAGE_UP], &[KeyboardKey::KEY_PAGE_DOWN]),
+        };
+        self.update(input, window.get_frame_time());
+    }
+
+    /// Advances the keyboard motion by `dt` seconds. Each axis has a velocity that eases
+    /// toward `input * speed` (rotation_speed for yaw and pitch, move_speed for zoom and
+    /// panning) at the rate set by `damping`, and the camera moves by velocity * dt, so
+    /// holding a key for one second covers about one second's worth of speed at any frame rate.
+    pub fn update(&mut self, input: CameraInput, dt: f32) {
+        let dt = dt.clamp(0.0, MAX_CAMERA_DT);
+        let targets = [
+            input.yaw * self.rotation_speed,
+            input.pitch * self.rotation_speed,
+            input.zoom * self.move_speed,
+            input.pan * self.move_speed,
+            input.lift * self.move_speed,
+        ];
+        // Exponential approach: frame-rate independent, and no overshoot
+        let blend = if self.damping > 0.0 { 1.0 - (-self.damping * dt).exp() } else { 1.0 };
+        for (velocity, target) in self.velocity.iter_mut().zip(targets) {
+            *velocity += (target - *velocity) * blend;
+            if velocity.abs() < 1e-4 && target == 0.0 {
+                *velocity = 0.0;
             }
-            self.update_eye_position();
         }
-        if window.is_key_down(KeyboardKey::KEY_DOWN) {
-            self.distance += self.zoom_speed;
-            self.update_eye_position();
+        if self.velocity.iter().all(|v| *v == 0.0) {
+            return;
         }
 
-        // Pan controls (move target/center point)
-        // Calculate right and forward vectors for panning
-        let forward = Vector3::new(
-            self.target.x - self.eye.x,
-            0.0, // Keep on horizontal plane
-            self.target.z - self.eye.z,
-        );
+        let [yaw, pitch, zoom, pan, lift] = self.velocity;
+        self.yaw += yaw * dt;
+        self.pitch += pitch * dt;
+        self.distance = (self.distance + zoom * dt).max(0.5); // Prevent camera from going too close
+
+        // Pan along the horizontal right vector of the view, and straight up for lift
+        let forward = Vector3::new(self.target.x - self.eye.x, 0.0, self.target.z - self.eye.z);
         let forward_len = (forward.x * forward.x + forward.z * forward.z).sqrt();
-        let forward_normalized = if forward_len > 0.0 {
-            Vector3::new(forward.x / forward_len, 0.0, forward.z / forward_len)
+        let (forward_x, forward_z) = if forward_len > 0.0 {
+            (forward.x / forward_len, forward.z / forward_len)
         } else {
-            Vector3::new(0.0, 0.0, 1.0)
+            (0.0, 1.0)
         };
+        let right = Vector3::new(forward_z, 0.0, -forward_x);
+        self.target.x += right.x * pan * dt;
+        self.target.z += right.z * pan * dt;
+        self.target.y += lift * dt;
 
-        let right = Vector3::new(
-            forward_normalized.z,
-            0.0,
-            -forward_normalized.x,
-        );
+        self.update_eye_position();
+    }
+}
 
-        // Q/E keys for horizontal panning
-        if window.is_key_down(KeyboardKey::KEY_Q) {
-            self.target.x += right.x * self.pan_speed;
-            self.target.z += right.z * self.pan_speed;
-            self.update_eye_position();
-        }
-        if window.is_key_down(KeyboardKey::KEY_E) {
-            self.target.x -= right.x * self.pan_speed;
-            self.target.z -= right.z * self.pan_speed;
-            self.update_eye_position();
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        // Left/Right arrow keys for horizontal panning
-        if window.is_key_down(KeyboardKey::KEY_LEFT) {
-            self.target.x += right.x * self.pan_speed;
-            self.target.z += right.z * self.pan_speed;
-            self.update_eye_position();
-        }
-        if window.is_key_down(KeyboardKey::KEY_RIGHT) {
-            self.target.x -= right.x * self.pan_speed;
-            self.target.z -= right.z * self.pan_speed;
-            self.update_eye_position();
+    fn assert_matrix_eq(a: &Matrix, b: &Matrix) {
+        let a = [a.m0, a.m1, a.m2, a.m3, a.m4, a.m5, a.m6, a.m7, a.m8, a.m9, a.m10, a.m11, a.m12, a.m13, a.m14, a.m15];
+        let b = [b.m0, b.m1, b.m2, b.m3, b.m4, b.m5, b.m6, b.m7, b.m8, b.m9, b.m10, b.m11, b.m12, b.m13, b.m14, b.m15];
+        for (x, y) in a.iter().zip(b.iter()) {
+            assert!((x - y).abs() < 1e-5, "{:?} != {:?}", a, b);
         }
+    }
 
-        // Vertical panning
-        if window.is_key_down(KeyboardKey::KEY_R) {
-            self.target.y += self.pan_speed;
-            self.update_eye_position();
-        }
-        if window.is_key_down(KeyboardKey::KEY_F) {
-            self.target.y -= self.pan_speed;
-            self.update_eye_position();
+    #[test]
+    fn reset_restores_the_initial_view() {
+        let mut camera = Camera::new(
+            Vector3::new(0.0, 0.0, 8.0),
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+        );
+        let initial = camera.get_view_matrix();
+
+        camera.eye = Vector3::new(3.0, -2.0, 5.0);
+        camera.orbit(1.0, 0.5);
+        camera.track(Vector3::new(1.0, 1.0, 1.0));
+        camera.reset();
+
+        assert_matrix_eq(&camera.get_view_matrix(), &initial);
+        assert!((camera.distance - 8.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn one_second_of_input_covers_move_speed() {
+        let mut camera = Camera::new(
+            Vector3::new(0.0, 0.0, 8.0),
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+        );
+        camera.damping = 0.0;
+        let speed = camera.move_speed;
+
+        let input = CameraInput { zoom: 1.0, lift: 1.0, ..CameraInput::default() };
+        for _ in 0..60 {
+            camera.update(input, 1.0 / 60.0);
         }
+
+        assert!((camera.distance - (8.0 + speed)).abs() < 1e-3, "distance {}", camera.distance);
+        assert!((camera.target.y - speed).abs() < 1e-3, "lift {}", camera.target.y);
     }
-}
\ No newline at end of file
+}
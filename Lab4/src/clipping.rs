@@ -0,0 +1,177 @@
+// clipping.rs
+use raylib::prelude::*;
+use crate::vertex::Vertex;
+use crate::matrix::multiply_matrix_vector4;
+
+// Minimum distance inside the near plane (z_clip + w_clip) a vertex must have
+const NEAR_EPSILON: f32 = 1e-5;
+
+/// Perspective divide followed by the viewport transform: clip space -> screen space.
+/// Only meaningful for `w > 0`. A vertex with `w <= 0` is at or behind the eye, and
+/// since the near plane sits in front of the eye (`z >= -w` implies `w >= near > 0` for
+/// the perspective projection, and `w` is always 1 for the orthographic one) such a
+/// vertex is always outside it. `clip_triangle` replaces it before its screen position
+/// is used. For `w == 0` the undivided position is returned only to avoid a NaN.
+pub fn clip_to_screen(clip_position: &Vector4, viewport_matrix: &Matrix) -> Vector3 {
+    let ndc = if clip_position.w != 0.0 {
+        Vector3::new(
+            clip_position.x / clip_position.w,
+            clip_position.y / clip_position.w,
+            clip_position.z / clip_position.w,
+        )
+    } else {
+        Vector3::new(clip_position.x, clip_position.y, clip_position.z)
+    };
+
+    let ndc_vec4 = Vector4::new(ndc.x, ndc.y, ndc.z, 1.0);
+    let screen_position = multiply_matrix_vector4(viewport_matrix, &ndc_vec4);
+    Vector3::new(screen_position.x, screen_position.y, screen_position.z)
+}
+
+// Signed distance to the near plane in clip space (OpenGL convention: inside when z >= -w)
+fn near_distance(vertex: &Vertex) -> f32 {
+    vertex.clip_position.z + vertex.clip_position.w
+}
+
+fn lerp3(a: Vector3, b: Vector3, t: f32) -> Vector3 {
+    Vector3::new(a.x + (b.x - a.x) * t, a.y + (b.y - a.y) * t, a.z + (b.z - a.z) * t)
+}
+
+// New vertex where the edge a -> b crosses the near plane, with every attribute interpolated
+fn intersect_near(a: &Vertex, b: &Vertex, viewport_matrix: &Matrix) -> Vertex {
+    let da = near_distance(a);
+    let db = near_distance(b);
+    let t = da / (da - db);
+
+    let clip_position = Vector4::new(
+        a.clip_position.x + (b.clip_position.x - a.clip_position.x) * t,
+        a.clip_position.y + (b.clip_position.y - a.clip_position.y) * t,
+        a.clip_position.z + (b.clip_position.z - a.clip_position.z) * t,
+        a.clip_position.w + (b.clip_position.w - a.clip_position.w) * t,
+    );
+
+    let mut transformed_normal = lerp3(a.transformed_normal, b.transformed_normal, t);
+    let len = (transformed_normal.x * transformed_normal.x + transformed_normal.y * transformed_normal.y + transformed_normal.z * transformed_normal.z).sqrt();
+    if len > 0.0 {
+        transformed_normal.x /= len;
+        transformed_normal.y /= len;
+        transformed_normal.z /= len;
+    }
+
+    Vertex {
+        position: lerp3(a.position, b.position, t),
+        normal: lerp3(a.normal, b.normal, t),
+        tex_coords: Vector2::new(
+            a.tex_coords.x + (b.tex_coords.x - a.tex_coords.x) * t,
+            a.tex_coords.y + (b.tex_coords.y - a.tex_coords.y) * t,
+        ),
+        color: lerp3(a.color, b.color, t),
+        transformed_position: clip_to_screen(&clip_position, viewport_matrix),
+        transformed_normal,
+        tangent: lerp3(a.tangent, b.tangent, t),
+        bitangent: lerp3(a.bitangent, b.bitangent, t),
+        transformed_tangent: lerp3(a.transformed_tangent, b.transformed_tangent, t),
+        transformed_bitangent: lerp3(a.transformed_bitangent, b.transformed_bitangent, t),
+        clip_position,
+    }
+}
+
+/// True when a screen-space triangle can't produce a visible pixel: its bounding box lies
+/// entirely outside the `width` x `height` screen, or all three vertices are past the same
+/// depth limit (NDC z outside [-1, 1]). Triangles that are only partly off screen are kept.
+pub fn is_offscreen(tri: &[Vertex; 3], width: i32, height: i32) -> bool {
+    let [a, b, c] = [&tri[0].transformed_position, &tri[1].transformed_position, &tri[2].transformed_position];
+
+    let min_x = a.x.min(b.x).min(c.x);
+    let max_x = a.x.max(b.x).max(c.x);
+    let min_y = a.y.min(b.y).min(c.y);
+    let max_y = a.y.max(b.y).max(c.y);
+    if max_x < 0.0 || min_x >= width as f32 || max_y < 0.0 || min_y >= height as f32 {
+        return true;
+    }
+
+    // The viewport keeps NDC z as the screen z
+    (a.z > 1.0 && b.z > 1.0 && c.z > 1.0) || (a.z < -1.0 && b.z < -1.0 && c.z < -1.0)
+}
+
+/// True when a screen-space triangle faces away from the camera. Meshes follow the OBJ
+/// convention (counter-clockwise seen from outside) and the viewport flips y, so a front
+/// face has negative signed area on screen. Zero-area triangles count as back faces:
+/// they can't cover a pixel either way.
+pub fn is_backface(tri: &[Vertex; 3]) -> bool {
+    let [a, b, c] = [&tri[0].transformed_position, &tri[1].transformed_position, &tri[2].transformed_position];
+    (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x) >= 0.0
+}
+
+/// Clips a triangle against the near plane before rasterization.
+/// Returns the triangle untouched when fully in front, nothing when fully behind,
+/// one smaller triangle when two vertices are behind, and two triangles
+/// (the clipped quad) when only one vertex is behind.
+/// Every vertex of the result has `w > 0`, so vertices behind the eye never reach
+/// the rasterizer and triangles entirely behind the camera are discarded here.
+pub fn clip_triangle(tri: &[Vertex; 3], viewport_matrix: &Matrix) -> Vec<[Vertex; 3]> {
+    let inside: Vec<usize> = (0..3).filter(|&i| near_distance(&tri[i]) >= NEAR_EPSILON).collect();
+
+    match inside.len() {
+        3 => vec![tri.clone()],
+        0 => Vec::new(),
+        1 => {
+            // Keep the original winding by walking the vertices in order from the inside one
+            let a = &tri[inside[0]];
+            let b = &tri[(inside[0] + 1) % 3];
+            let c = &tri[(inside[0] + 2) % 3];
+            vec![[
+                a.clone(),
+                intersect_near(a, b, viewport_matrix),
+                intersect_near(a, c, viewport_matrix),
+            ]]
+        }
+        _ => {
+            // The single outside vertex is cut off, leaving a quad split into two triangles
+            let outside = (0..3).find(|i| !inside.contains(i)).unwrap_or(0);
+            let c = &tri[outside];
+            let a = &tri[(outside + 1) % 3];
+            let b = &tri[(outside + 2) % 3];
+            let bc = intersect_near(b, c, viewport_matrix);
+            let ca = intersect_near(a, c, viewport_matrix);
+            vec![
+                [a.clone(), b.clone(), bc.clone()],
+                [a.clone(), bc, ca],
+            ]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matrix::{create_projection_matrix, create_viewport_matrix};
+
+    // Vertex at a view-space position, projected like the vertex shader does
+    fn view_vertex(position: Vector3, viewport_matrix: &Matrix) -> Vertex {
+        let projection = create_projection_matrix(std::f32::consts::PI / 3.0, 1.0, 0.1, 100.0);
+        let mut vertex = Vertex::new(position, Vector3::new(0.0, 0.0, 1.0), Vector2::new(0.0, 0.0));
+        vertex.clip_position = multiply_matrix_vector4(&projection, &Vector4::new(position.x, position.y, position.z, 1.0));
+        vertex.transformed_position = clip_to_screen(&vertex.clip_position, viewport_matrix);
+        vertex
+    }
+
+    #[test]
+    fn vertex_behind_the_eye_is_clipped_away() {
+        let viewport = create_viewport_matrix(0.0, 0.0, 100.0, 100.0);
+        // The camera looks down -Z, so the first vertex is behind it
+        let tri = [
+            view_vertex(Vector3::new(0.0, 0.0, 2.0), &viewport),
+            view_vertex(Vector3::new(-1.0, 0.0, -5.0), &viewport),
+            view_vertex(Vector3::new(1.0, 1.0, -5.0), &viewport),
+        ];
+        assert!(tri[0].clip_position.w <= 0.0);
+
+        let clipped = clip_triangle(&tri, &viewport);
+        assert_eq!(clipped.len(), 2);
+        for vertex in clipped.iter().flatten() {
+            assert!(vertex.clip_position.w > 0.0, "w = {}", vertex.clip_position.w);
+            assert!(near_distance(vertex) > -1e-5);
+        }
+    }
+}
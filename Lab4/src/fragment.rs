@@ -1,20 +1,36 @@
 // fragment.rs
-use raylib::prelude::Vector3;
+#![allow(dead_code)]
+
+use raylib::prelude::{Vector2, Vector3};
 
 pub struct Fragment {
     pub position: Vector3, // screen position
     pub color: Vector3,
     pub depth: f32,
     pub world_position: Vector3,
+    pub tex_coords: Vector2,
+    pub normal: Vector3, // interpolated normal, in the body's own coordinates like world_position
+    pub tangent: Vector3,   // tangent frame for normal mapping, same space,
+    pub bitangent: Vector3, // zero when the mesh has none
 }
 
 impl Fragment {
-    pub fn new(x: f32, y: f32, color: Vector3, depth: f32, world_position: Vector3) -> Self {
+    pub fn new(x: f32, y: f32, color: Vector3, depth: f32, world_position: Vector3, tex_coords: Vector2, normal: Vector3) -> Self {
         Fragment {
             position: Vector3::new(x, y, depth), // La z se actualiza con depth
             color,
             depth,
             world_position,
+            tex_coords,
+            normal,
+            tangent: Vector3::new(0.0, 0.0, 0.0),
+            bitangent: Vector3::new(0.0, 0.0, 0.0),
         }
     }
+
+    pub fn with_tangent_frame(mut self, tangent: Vector3, bitangent: Vector3) -> Self {
+        self.tangent = tangent;
+        self.bitangent = bitangent;
+        self
+    }
 }
\ No newline at end of file
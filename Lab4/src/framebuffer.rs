@@ -1,33 +1,351 @@
 // framebuffer.rs
 use raylib::prelude::*;
 
+// Depth value of an empty pixel: anything rasterized is closer than this
+const FAR_DEPTH: f32 = f32::INFINITY;
+
+// Empty pixels in the depth view. Not a gray, so they can't be mistaken for any depth
+const EMPTY_DEPTH_COLOR: Color = Color::new(0, 0, 96, 255);
+
+// Fraction of a star's brightness that twinkling can take away
+const TWINKLE_DEPTH: f32 = 0.35;
+
+// Ordered dithering thresholds (4x4 Bayer matrix), in sixteenths of one 8-bit step
+const BAYER_4X4: [[u8; 4]; 4] = [
+    [0, 8, 2, 10],
+    [12, 4, 14, 6],
+    [3, 11, 1, 9],
+    [15, 7, 13, 5],
+];
+
+/// Depth test results for the fragments written since the last `clear`
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FragmentStats {
+    /// Every fragment handed to `point` or `blend_point`, including ones outside the buffer
+    pub fragments: usize,
+    /// Fragments closer than what was stored, which were written (or blended)
+    pub passed: usize,
+    /// Fragments hidden behind what was stored; they were shaded for nothing
+    pub rejected: usize,
+}
+
 pub struct Framebuffer {
+    // Render resolution: the display size times the supersampling factor
     pub width: i32,
     pub height: i32,
     pub color_buffer: Image,
+    display_width: i32,
+    display_height: i32,
+    supersample: i32,
     background_color: Color,
     current_color: Color,
     depth_buffer: Vec<f32>,
+    // Bright-pass and blur buffers for the bloom pass, kept between frames
+    bloom_buffer: Vec<Vector3>,
+    bloom_temp: Vec<Vector3>,
+    // Output gamma encoding per channel value, None when disabled
+    gamma_lut: Option<[u8; 256]>,
+    // Silhouette outline applied when presenting: (depth gradient threshold, color)
+    outline: Option<(f32, Color)>,
+    // Corner darkening applied when presenting: (strength, inner radius)
+    vignette: Option<(f32, f32)>,
+    fragment_stats: FragmentStats,
+    // When false every fragment is written in drawing order, ignoring the depth buffer
+    depth_test: bool,
+    // Ordered dithering when float colors are stored as 8 bits (and when posterizing)
+    dither: bool,
+    // Output levels per channel, applied last when presenting; None keeps all 256
+    quantize_levels: Option<u8>,
 }
 
 impl Framebuffer {
+    /// Creates a framebuffer entirely in main memory: the color buffer is a raylib
+    /// `Image` (CPU pixels, no GL), so rendering works without a window.
+    /// Only `swap_buffers` needs one, to upload the frame as a texture.
     pub fn new(width: i32, height: i32) -> Self {
         let background_color = Color::BLACK; // Un color por defecto
         let color_buffer = Image::gen_image_color(width, height, background_color);
-        let depth_buffer = vec![f32::INFINITY; (width * height) as usize];
+        let depth_buffer = vec![FAR_DEPTH; (width * height) as usize];
         Framebuffer {
             width,
             height,
             color_buffer,
+            display_width: width,
+            display_height: height,
+            supersample: 1,
             background_color,
             current_color: Color::WHITE,
             depth_buffer,
+            bloom_buffer: Vec::new(),
+            bloom_temp: Vec::new(),
+            gamma_lut: None,
+            outline: None,
+            vignette: None,
+            fragment_stats: FragmentStats::default(),
+            depth_test: true,
+            dither: false,
+            quantize_levels: None,
+        }
+    }
+
+    /// Renders internally at `factor` times the display resolution (1 disables it).
+    /// The color and depth buffers are reallocated at the new size and
+    /// box-filtered back down to the display size when presenting.
+    pub fn set_supersample(&mut self, factor: i32) {
+        let factor = factor.max(1);
+        if factor == self.supersample {
+            return;
+        }
+        self.supersample = factor;
+        self.width = self.display_width * factor;
+        self.height = self.display_height * factor;
+        self.color_buffer = Image::gen_image_color(self.width, self.height, self.background_color);
+        self.depth_buffer = vec![FAR_DEPTH; (self.width * self.height) as usize];
+    }
+
+    pub fn supersample(&self) -> i32 {
+        self.supersample
+    }
+
+    /// Enables gamma correction of the presented (and saved) image with `c^(1/gamma)`,
+    /// or disables it with None. Shading and blending stay linear; the curve is applied
+    /// only when the frame leaves the framebuffer.
+    pub fn set_gamma(&mut self, gamma: Option<f32>) {
+        self.gamma_lut = gamma.filter(|g| *g > 0.0).map(|gamma| {
+            let mut lut = [0u8; 256];
+            for (i, value) in lut.iter_mut().enumerate() {
+                // Clamp first so pow never sees a value outside [0, 1]
+                let linear = (i as f32 / 255.0).clamp(0.0, 1.0);
+                *value = (linear.powf(1.0 / gamma) * 255.0).round() as u8;
+            }
+            lut
+        });
+    }
+
+    /// Enables a dark outline drawn when presenting (and saving): pixels where the Sobel
+    /// gradient of the depth buffer exceeds `threshold` are blended toward `color`,
+    /// which traces the silhouettes of the planet and moons. None disables it.
+    pub fn set_outline(&mut self, outline: Option<(f32, Color)>) {
+        self.outline = outline;
+    }
+
+    /// Enables a vignette applied when presenting (and saving), after shading and tone
+    /// mapping and before the overlay text. Display pixels are multiplied by
+    /// `1 - strength * smoothstep(radius, 1, dist)`, where `dist` is the distance from
+    /// the center with the corners at 1, so everything inside `radius` keeps full brightness.
+    /// None disables it.
+    pub fn set_vignette(&mut self, vignette: Option<(f32, f32)>) {
+        self.vignette = vignette;
+    }
+
+    // Brightness factor of the vignette at display pixel (x, y)
+    fn vignette_factor(&self, x: i32, y: i32) -> f32 {
+        let (strength, radius) = match self.vignette {
+            Some(vignette) => vignette,
+            None => return 1.0,
+        };
+        let half_width = self.display_width as f32 * 0.5;
+        let half_height = self.display_height as f32 * 0.5;
+        let dx = x as f32 + 0.5 - half_width;
+        let dy = y as f32 + 0.5 - half_height;
+        let dist = (dx * dx + dy * dy).sqrt() / (half_width * half_width + half_height * half_height).sqrt();
+
+        let t = ((dist - radius) / (1.0 - radius).max(f32::EPSILON)).clamp(0.0, 1.0);
+        1.0 - strength.clamp(0.0, 1.0) * t * t * (3.0 - 2.0 * t)
+    }
+
+    // Depth at (x, y) clamped to the buffer edges, with empty pixels at the far plane (NDC z = 1)
+    fn edge_depth(&self, x: i32, y: i32) -> f32 {
+        let x = x.clamp(0, self.width - 1);
+        let y = y.clamp(0, self.height - 1);
+        let depth = self.depth_buffer[(y * self.width + x) as usize];
+        if depth.is_finite() { depth } else { 1.0 }
+    }
+
+    // Render pixel (x, y) with the outline stage applied
+    fn outlined_color(&self, x: i32, y: i32) -> Color {
+        let color = self.color_buffer.get_color(x, y);
+        let (threshold, outline) = match self.outline {
+            Some(outline) => outline,
+            None => return color,
+        };
+
+        let d = |dx: i32, dy: i32| self.edge_depth(x + dx, y + dy);
+        let gx = (d(1, -1) + 2.0 * d(1, 0) + d(1, 1)) - (d(-1, -1) + 2.0 * d(-1, 0) + d(-1, 1));
+        let gy = (d(-1, 1) + 2.0 * d(0, 1) + d(1, 1)) - (d(-1, -1) + 2.0 * d(0, -1) + d(1, -1));
+        let magnitude = (gx * gx + gy * gy).sqrt();
+
+        // Ramp from threshold to 2x threshold instead of a hard cut, so the line is antialiased
+        let t = ((magnitude - threshold) / threshold.max(f32::EPSILON)).clamp(0.0, 1.0);
+        if t <= 0.0 {
+            return color;
         }
+        let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t) as u8;
+        Color::new(mix(color.r, outline.r), mix(color.g, outline.g), mix(color.b, outline.b), 255)
     }
 
+    // Final color of display pixel (x, y): supersampling resolve, vignette, gamma, then
+    // the palette reduction
+    fn resolved_color(&self, x: i32, y: i32) -> Color {
+        let mut color = self.averaged_color(x, y);
+        let factor = self.vignette_factor(x, y);
+        if factor < 1.0 {
+            let scale = |c: u8| self.to_u8(c as f32 * factor, x, y);
+            color = Color::new(scale(color.r), scale(color.g), scale(color.b), color.a);
+        }
+        if let Some(lut) = &self.gamma_lut {
+            color = Color::new(lut[color.r as usize], lut[color.g as usize], lut[color.b as usize], color.a);
+        }
+        if self.quantize_levels.is_some() {
+            let snap = |c: u8| self.posterize(c, x, y);
+            color = Color::new(snap(color.r), snap(color.g), snap(color.b), color.a);
+        }
+        color
+    }
+
+    // Average of the factor x factor block of render pixels behind display pixel (x, y)
+    fn averaged_color(&self, x: i32, y: i32) -> Color {
+        let factor = self.supersample;
+        if factor == 1 {
+            return self.outlined_color(x, y);
+        }
+
+        let (mut r, mut g, mut b) = (0u32, 0u32, 0u32);
+        for sy in 0..factor {
+            for sx in 0..factor {
+                let color = self.outlined_color(x * factor + sx, y * factor + sy);
+                r += color.r as u32;
+                g += color.g as u32;
+                b += color.b as u32;
+            }
+        }
+        let samples = (factor * factor) as u32;
+        Color::new((r / samples) as u8, (g / samples) as u8, (b / samples) as u8, 255)
+    }
+
+    // Display-sized image of the final frame
+    fn resolve(&self) -> Image {
+        let mut image = Image::gen_image_color(self.display_width, self.display_height, self.background_color);
+        for y in 0..self.display_height {
+            for x in 0..self.display_width {
+                image.draw_pixel(x, y, self.resolved_color(x, y));
+            }
+        }
+        image
+    }
+
+    /// Clears the color buffer to the background color, resets every depth to the far plane
+    /// and zeroes the fragment counters
     pub fn clear(&mut self) {
         self.color_buffer.clear_background(self.background_color);
-        self.depth_buffer.fill(f32::INFINITY);
+        self.depth_buffer.fill(FAR_DEPTH);
+        self.fragment_stats = FragmentStats::default();
+    }
+
+    /// Turns ordered dithering on or off (the default). With it every float color is
+    /// nudged by a 4x4 Bayer threshold of less than one 8-bit step before being
+    /// truncated, so smooth gradients become a fine, even pattern instead of
+    /// visible bands. The average over each 4x4 block keeps the original value.
+    pub fn set_dither(&mut self, enabled: bool) {
+        self.dither = enabled;
+    }
+
+    /// Reduces the presented (and saved) image to `levels` evenly spaced values per
+    /// channel, e.g. 2 for 8 colors or 6 for the 216-color web palette. Each channel
+    /// snaps to the nearest step after gamma. With dithering on, the Bayer threshold
+    /// picks between the two nearest steps instead, which turns gradients into a
+    /// pixel-art pattern. None (or fewer than 2 levels) disables it.
+    pub fn set_quantize(&mut self, levels: Option<u8>) {
+        self.quantize_levels = levels.filter(|levels| *levels >= 2);
+    }
+
+    // Channel value in [0, 255] to 8 bits: truncated, after the dither threshold of (x, y)
+    fn to_u8(&self, value: f32, x: i32, y: i32) -> u8 {
+        let threshold = if self.dither { bayer_threshold(x, y) } else { 0.0 };
+        (value + threshold).clamp(0.0, 255.0) as u8
+    }
+
+    // Channel snapped to one of the `quantize_levels` steps: the nearest one, or
+    // chosen by the dither threshold of (x, y)
+    fn posterize(&self, c: u8, x: i32, y: i32) -> u8 {
+        let levels = match self.quantize_levels {
+            Some(levels) => levels,
+            None => return c,
+        };
+        let last = (levels - 1) as f32;
+        let step = 255.0 / last;
+        let threshold = if self.dither { bayer_threshold(x, y) } else { 0.5 };
+        ((c as f32 / step + threshold).floor().min(last) * step).round() as u8
+    }
+
+    /// Turns the depth test on (the default) or off. Without it fragments overwrite each
+    /// other in drawing order, so with back-face culling also off the far side of a
+    /// sphere shows through. Depth is still recorded for the depth view and the outline.
+    pub fn set_depth_test(&mut self, enabled: bool) {
+        self.depth_test = enabled;
+    }
+
+    /// Fragment and depth test counts since the last `clear`. Overdraw shows up as
+    /// `rejected` fragments and as `passed` exceeding the number of covered pixels.
+    pub fn fragment_stats(&self) -> FragmentStats {
+        self.fragment_stats
+    }
+
+    /// Scatters a deterministic starfield over the color buffer; call it right after `clear`.
+    /// `density` is the fraction of display pixels holding a star and the same `seed` always
+    /// gives the same sky. Stars are placed at display resolution so supersampling doesn't
+    /// change them. The depth buffer stays at the far plane, so the planet covers them.
+    /// Each star twinkles with `time` at its own rate and phase, only ever dimming, so
+    /// positions never move and no star gets brighter than it is at rest.
+    pub fn render_starfield(&mut self, seed: u32, density: f32, time: f32) {
+        for y in 0..self.display_height {
+            for x in 0..self.display_width {
+                let h = hash2(x as u32, y as u32, seed);
+                let chance = (h & 0xFFFF) as f32 / 65535.0;
+                if chance >= density {
+                    continue;
+                }
+                let brightness = ((h >> 16) & 0xFF) as f32 / 255.0;
+                let blue_shift = ((h >> 24) & 0xFF) as f32 / 255.0 * 0.3;
+
+                // Unas pocas estrellas brillantes con un halo en cruz; el resto tenues
+                let bright = brightness > 0.95;
+                let level = if bright { 1.0 } else { 0.25 + 0.5 * brightness };
+
+                // Second hash for the twinkle, so it doesn't follow brightness or color
+                let t = hash2(x as u32, y as u32, seed ^ 0x9e37_79b9);
+                let phase = (t & 0xFFFF) as f32 / 65535.0 * std::f32::consts::TAU;
+                let rate = 1.5 + (t >> 16) as f32 / 65535.0 * 2.0;
+                let twinkle = 1.0 - TWINKLE_DEPTH * (0.5 + 0.5 * (time * rate + phase).sin());
+
+                let star = |level: f32| Color::new(
+                    (level * (1.0 - blue_shift) * 255.0) as u8,
+                    (level * (1.0 - blue_shift * 0.5) * 255.0) as u8,
+                    (level * 255.0) as u8,
+                    255,
+                );
+
+                self.fill_display_pixel(x, y, star(level * twinkle));
+                if bright {
+                    for (dx, dy) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
+                        self.fill_display_pixel(x + dx, y + dy, star(0.4 * twinkle));
+                    }
+                }
+            }
+        }
+    }
+
+    // Paints the supersample x supersample block behind display pixel (x, y)
+    fn fill_display_pixel(&mut self, x: i32, y: i32, color: Color) {
+        if x < 0 || x >= self.display_width || y < 0 || y >= self.display_height {
+            return;
+        }
+        let factor = self.supersample;
+        for sy in 0..factor {
+            for sx in 0..factor {
+                self.color_buffer.draw_pixel(x * factor + sx, y * factor + sy, color);
+            }
+        }
     }
 
     pub fn set_pixel(&mut self, x: i32, y: i32) {
@@ -36,24 +354,60 @@ impl Framebuffer {
         }
     }
     
+    /// Writes a shaded fragment if it passes the depth test.
+    /// Smaller depth means closer to the camera; fragments at the same or greater
+    /// depth than the stored value (or NaN) are rejected.
     pub fn point(&mut self, x: i32, y: i32, color: Vector3, depth: f32) {
+        self.fragment_stats.fragments += 1;
         if x >= 0 && x < self.width && y >= 0 && y < self.height {
             let index = (y * self.width + x) as usize;
 
-            if depth < self.depth_buffer[index] {
+            if depth < self.depth_buffer[index] || !self.depth_test {
+                self.fragment_stats.passed += 1;
                 self.depth_buffer[index] = depth;
                 let pixel_color = Color::new(
-                    (color.x.clamp(0.0, 1.0) * 255.0) as u8,
-                    (color.y.clamp(0.0, 1.0) * 255.0) as u8,
-                    (color.z.clamp(0.0, 1.0) * 255.0) as u8,
+                    self.to_u8(color.x.clamp(0.0, 1.0) * 255.0, x, y),
+                    self.to_u8(color.y.clamp(0.0, 1.0) * 255.0, x, y),
+                    self.to_u8(color.z.clamp(0.0, 1.0) * 255.0, x, y),
+                    255,
+                );
+                self.color_buffer.draw_pixel(x, y, pixel_color);
+            } else {
+                self.fragment_stats.rejected += 1;
+            }
+        }
+    }
+    
+    /// Blends a translucent fragment over the stored color: `src*alpha + dst*(1-alpha)`.
+    /// Uses the same depth test as `point` but never writes depth, so whatever is
+    /// drawn behind it later still shows through.
+    pub fn blend_point(&mut self, x: i32, y: i32, color: Vector3, alpha: f32, depth: f32) {
+        self.fragment_stats.fragments += 1;
+        if x >= 0 && x < self.width && y >= 0 && y < self.height {
+            let index = (y * self.width + x) as usize;
+
+            if depth < self.depth_buffer[index] || !self.depth_test {
+                self.fragment_stats.passed += 1;
+                let alpha = alpha.clamp(0.0, 1.0);
+                let dst = self.color_buffer.get_color(x, y);
+                let blend = |src: f32, dst: u8| {
+                    self.to_u8(src.clamp(0.0, 1.0) * 255.0 * alpha + dst as f32 * (1.0 - alpha), x, y)
+                };
+                let pixel_color = Color::new(
+                    blend(color.x, dst.r),
+                    blend(color.y, dst.g),
+                    blend(color.z, dst.b),
                     255,
                 );
                 self.color_buffer.draw_pixel(x, y, pixel_color);
+            } else {
+                self.fragment_stats.rejected += 1;
             }
         }
     }
     
-    pub fn get_pixel_color(&mut self, x: i32, y: i32) -> Option<Color> {
+    /// Returns the stored color at (x, y), or None outside the buffer
+    pub fn get_pixel(&self, x: i32, y: i32) -> Option<Color> {
         if x >= 0 && x < self.width && y >= 0 && y < self.height {
             Some(self.color_buffer.get_color(x, y))
         } else {
@@ -61,6 +415,137 @@ impl Framebuffer {
         }
     }
 
+    /// Returns the stored depth at (x, y), or None outside the buffer
+    pub fn get_depth(&self, x: i32, y: i32) -> Option<f32> {
+        if x >= 0 && x < self.width && y >= 0 && y < self.height {
+            Some(self.depth_buffer[(y * self.width + x) as usize])
+        } else {
+            None
+        }
+    }
+
+    /// Builds a grayscale view of the depth buffer (row-major, one Color per pixel).
+    /// Depths are normalized between the nearest and farthest written fragments:
+    /// near is white and far is dark gray, never black. Cleared pixels (infinite depth)
+    /// get `EMPTY_DEPTH_COLOR`, a dark blue that no depth maps to.
+    pub fn depth_to_grayscale(&self) -> Vec<Color> {
+        let mut min_depth = f32::INFINITY;
+        let mut max_depth = f32::NEG_INFINITY;
+        for &depth in &self.depth_buffer {
+            if depth.is_finite() {
+                min_depth = min_depth.min(depth);
+                max_depth = max_depth.max(depth);
+            }
+        }
+
+        let range = max_depth - min_depth;
+        self.depth_buffer
+            .iter()
+            .map(|&depth| {
+                if !depth.is_finite() {
+                    return EMPTY_DEPTH_COLOR;
+                }
+                // All fragments at the same depth: avoid dividing by zero
+                let value = if range > f32::EPSILON {
+                    (1.0 - (depth - min_depth) / range) * 225.0 + 30.0
+                } else {
+                    128.0
+                };
+                Color::new(value as u8, value as u8, value as u8, 255)
+            })
+            .collect()
+    }
+
+    /// Replaces the color buffer with the depth visualization
+    pub fn show_depth(&mut self) {
+        let gray = self.depth_to_grayscale();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                self.color_buffer.draw_pixel(x, y, gray[(y * self.width + x) as usize]);
+            }
+        }
+    }
+
+    /// Post-process bloom. Pixels whose luminance is above `threshold` (0..1) are
+    /// copied to a scratch buffer, blurred with a separable Gaussian of `radius`
+    /// pixels and added back on top of the color buffer.
+    pub fn apply_bloom(&mut self, threshold: f32, radius: i32) {
+        if radius <= 0 {
+            return;
+        }
+        let size = (self.width * self.height) as usize;
+        self.bloom_buffer.clear();
+        self.bloom_buffer.resize(size, Vector3::zero());
+        self.bloom_temp.clear();
+        self.bloom_temp.resize(size, Vector3::zero());
+
+        // Bright pass
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let color = self.color_buffer.get_color(x, y);
+                let rgb = Vector3::new(color.r as f32 / 255.0, color.g as f32 / 255.0, color.b as f32 / 255.0);
+                let luminance = 0.2126 * rgb.x + 0.7152 * rgb.y + 0.0722 * rgb.z;
+                if luminance > threshold {
+                    self.bloom_buffer[(y * self.width + x) as usize] = rgb;
+                }
+            }
+        }
+
+        // Gaussian weights with sigma = radius / 2, normalized to sum 1
+        let sigma = radius as f32 * 0.5;
+        let mut weights: Vec<f32> = (-radius..=radius)
+            .map(|i| (-((i * i) as f32) / (2.0 * sigma * sigma)).exp())
+            .collect();
+        let total: f32 = weights.iter().sum();
+        for weight in weights.iter_mut() {
+            *weight /= total;
+        }
+
+        // Horizontal pass: bloom_buffer -> bloom_temp
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let mut sum = Vector3::zero();
+                for (k, weight) in weights.iter().enumerate() {
+                    let sx = (x + k as i32 - radius).clamp(0, self.width - 1);
+                    sum += self.bloom_buffer[(y * self.width + sx) as usize] * *weight;
+                }
+                self.bloom_temp[(y * self.width + x) as usize] = sum;
+            }
+        }
+
+        // Vertical pass: bloom_temp -> bloom_buffer
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let mut sum = Vector3::zero();
+                for (k, weight) in weights.iter().enumerate() {
+                    let sy = (y + k as i32 - radius).clamp(0, self.height - 1);
+                    sum += self.bloom_temp[(sy * self.width + x) as usize] * *weight;
+                }
+                self.bloom_buffer[(y * self.width + x) as usize] = sum;
+            }
+        }
+
+        // Additive composite
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let glow = self.bloom_buffer[(y * self.width + x) as usize];
+                if glow.x <= 0.0 && glow.y <= 0.0 && glow.z <= 0.0 {
+                    continue;
+                }
+                let color = self.color_buffer.get_color(x, y);
+                let pixel_color = Color::new(
+                    (color.r as f32 + glow.x * 255.0).min(255.0) as u8,
+                    (color.g as f32 + glow.y * 255.0).min(255.0) as u8,
+                    (color.b as f32 + glow.z * 255.0).min(255.0) as u8,
+                    255,
+                );
+                self.color_buffer.draw_pixel(x, y, pixel_color);
+            }
+        }
+    }
+
+    /// Color that `clear` fills the color buffer with; takes effect on the next clear,
+    /// so it can change between frames
     pub fn set_background_color(&mut self, color: Color) {
         self.background_color = color;
     }
@@ -69,11 +554,157 @@ impl Framebuffer {
         self.current_color = color;
     }
 
-    pub fn swap_buffers(&self, d: &mut RaylibHandle, thread: &RaylibThread) {
-        if let Ok(texture) = d.load_texture_from_image(thread, &self.color_buffer) {
+    /// Writes the current color buffer to disk as a PNG, at display resolution.
+    /// The buffer is stored top-down (row 0 is the top of the screen), same as PNG,
+    /// so no vertical flip is needed.
+    pub fn save_png(&self, path: &str) -> Result<(), std::io::Error> {
+        let mut output = image::RgbaImage::new(self.display_width as u32, self.display_height as u32);
+        for y in 0..self.display_height {
+            for x in 0..self.display_width {
+                let color = self.resolved_color(x, y);
+                // Alpha fijo en 255 para que el PNG se vea igual en cualquier visor
+                output.put_pixel(x as u32, y as u32, image::Rgba([color.r, color.g, color.b, 255]));
+            }
+        }
+        output.save(path).map_err(std::io::Error::other)
+    }
+
+    /// Writes the frame as a binary PPM (P6) at display resolution, with the same
+    /// post-processing as `save_png` but using only std: a text header followed by
+    /// the raw RGB bytes, rows top to bottom.
+    pub fn save_ppm(&self, path: &str) -> Result<(), std::io::Error> {
+        let mut data = format!("P6\n{} {}\n255\n", self.display_width, self.display_height).into_bytes();
+        data.reserve((self.display_width * self.display_height * 3) as usize);
+        for y in 0..self.display_height {
+            for x in 0..self.display_width {
+                let color = self.resolved_color(x, y);
+                data.extend([color.r, color.g, color.b]);
+            }
+        }
+        std::fs::write(path, data)
+    }
+
+    /// Presents the frame. `overlay` lines are drawn as text on top of the
+    /// framebuffer texture inside the same drawing scope, so nothing clears them.
+    pub fn swap_buffers(&self, d: &mut RaylibHandle, thread: &RaylibThread, overlay: &[String]) {
+        let texture = if self.supersample > 1
+            || self.gamma_lut.is_some()
+            || self.outline.is_some()
+            || self.vignette.is_some()
+            || self.quantize_levels.is_some()
+        {
+            d.load_texture_from_image(thread, &self.resolve())
+        } else {
+            d.load_texture_from_image(thread, &self.color_buffer)
+        };
+        if let Ok(texture) = texture {
             let mut d = d.begin_drawing(thread);
             d.clear_background(self.background_color);
             d.draw_texture(&texture, 0, 0, Color::WHITE);
+            for (i, line) in overlay.iter().enumerate() {
+                d.draw_text(line, 10, 10 + i as i32 * 22, 20, Color::WHITE);
+            }
         }
     } 
-}
\ No newline at end of file
+}
+
+// Ordered dithering threshold of pixel (x, y), in [0, 1)
+fn bayer_threshold(x: i32, y: i32) -> f32 {
+    (BAYER_4X4[(y & 3) as usize][(x & 3) as usize] as f32 + 0.5) / 16.0
+}
+
+// Integer hash of a pixel and a seed, well mixed in every bit
+fn hash2(x: u32, y: u32, seed: u32) -> u32 {
+    let mut h = x.wrapping_mul(0x8da6_b343) ^ y.wrapping_mul(0xd816_3841) ^ seed.wrapping_mul(0xcb1a_b31f);
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x7feb_352d);
+    h ^= h >> 15;
+    h = h.wrapping_mul(0x846c_a68b);
+    h ^= h >> 16;
+    h
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: Vector3 = Vector3::new(1.0, 0.0, 0.0);
+    const BLUE: Vector3 = Vector3::new(0.0, 0.0, 1.0);
+
+    #[test]
+    fn nearer_point_wins_in_either_order() {
+        let mut framebuffer = Framebuffer::new(4, 4);
+        framebuffer.clear();
+        framebuffer.point(1, 1, RED, 0.9);
+        framebuffer.point(1, 1, BLUE, 0.2);
+        assert_eq!(framebuffer.get_depth(1, 1), Some(0.2));
+        assert_eq!(framebuffer.color_buffer.get_color(1, 1), Color::new(0, 0, 255, 255));
+
+        framebuffer.clear();
+        framebuffer.point(1, 1, BLUE, 0.2);
+        framebuffer.point(1, 1, RED, 0.9);
+        assert_eq!(framebuffer.get_depth(1, 1), Some(0.2));
+        assert_eq!(framebuffer.color_buffer.get_color(1, 1), Color::new(0, 0, 255, 255));
+    }
+
+    #[test]
+    fn half_red_over_blue_is_purple() {
+        let mut framebuffer = Framebuffer::new(4, 4);
+        framebuffer.clear();
+        framebuffer.point(2, 2, BLUE, 0.5);
+        framebuffer.blend_point(2, 2, RED, 0.5, 0.1);
+        let color = framebuffer.get_pixel(2, 2).unwrap();
+        assert!(color.r.abs_diff(128) <= 1 && color.g == 0 && color.b.abs_diff(128) <= 1, "{:?}", color);
+        // Blending leaves the depth of the opaque fragment behind it
+        assert_eq!(framebuffer.get_depth(2, 2), Some(0.5));
+    }
+
+    #[test]
+    fn empty_pixels_stand_apart_in_the_depth_view() {
+        let mut framebuffer = Framebuffer::new(4, 1);
+        framebuffer.clear();
+        framebuffer.point(0, 0, RED, 0.1);
+        framebuffer.point(1, 0, RED, 0.9);
+        let gray = framebuffer.depth_to_grayscale();
+        let (near, far, empty) = (gray[0], gray[1], gray[3]);
+        assert!(near.r > far.r, "{:?} {:?}", near, far);
+        assert_ne!(empty, near);
+        assert_ne!(empty, far);
+        assert_ne!(empty, Color::BLACK);
+    }
+
+    #[test]
+    fn get_pixel_reads_back_what_was_drawn() {
+        let mut framebuffer = Framebuffer::new(4, 4);
+        framebuffer.clear();
+        framebuffer.point(3, 0, RED, 0.5);
+        assert_eq!(framebuffer.get_pixel(3, 0), Some(Color::new(255, 0, 0, 255)));
+        assert_eq!(framebuffer.get_pixel(0, 3), Some(Color::BLACK));
+    }
+
+    #[test]
+    fn get_pixel_outside_the_buffer_is_none() {
+        let framebuffer = Framebuffer::new(4, 4);
+        for (x, y) in [(-1, 0), (0, -1), (4, 0), (0, 4)] {
+            assert_eq!(framebuffer.get_pixel(x, y), None);
+        }
+    }
+
+    #[test]
+    fn save_ppm_writes_header_and_pixels() {
+        let mut framebuffer = Framebuffer::new(2, 2);
+        framebuffer.clear();
+        framebuffer.point(0, 0, RED, 0.5);
+        framebuffer.point(1, 0, BLUE, 0.5);
+        framebuffer.point(0, 1, Vector3::new(0.0, 1.0, 0.0), 0.5);
+        framebuffer.point(1, 1, Vector3::new(1.0, 1.0, 1.0), 0.5);
+
+        let path = std::env::temp_dir().join(format!("framebuffer_test_{}.ppm", std::process::id()));
+        framebuffer.save_ppm(path.to_str().unwrap()).unwrap();
+        let data = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let header = b"P6\n2 2\n255\n";
+        assert_eq!(&data[..header.len()], header);
+        assert_eq!(&data[header.len()..], [255, 0, 0, 0, 0, 255, 0, 255, 0, 255, 255, 255]);
+    }
+}
@@ -0,0 +1,350 @@
+// lib.rs
+//! Software rasterizer behind the planet viewer. Everything runs on the CPU: load a mesh
+//! with `Obj`, fill a `Uniforms` (matrices from `matrix`, a `Camera` view), and draw
+//! with `render_scene`, `render_planet` or, for custom shading, `render_mesh` with a
+//! closure that returns a `FragmentOutput` per fragment. The result stays in a
+//! `Framebuffer`, which can be saved with `save_png` / `save_ppm` without a window;
+//! only `Framebuffer::swap_buffers` needs a raylib window.
+pub mod framebuffer;
+pub mod triangle;
+pub mod obj;
+pub mod matrix;
+pub mod fragment;
+pub mod vertex;
+pub mod camera;
+pub mod shaders;
+pub mod light;
+pub mod clipping;
+pub mod pipeline;
+pub mod texture;
+
+use raylib::prelude::*;
+use std::sync::Arc;
+use shaders::{fragment_shader, object_space_lights, object_space_point, render_rings, render_moon, render_clouds};
+
+// The types and entry points needed to drive the renderer from another crate
+pub use framebuffer::Framebuffer;
+pub use obj::Obj;
+pub use vertex::Vertex;
+pub use fragment::Fragment;
+pub use camera::Camera;
+pub use light::{Light, LightKind};
+pub use texture::Texture;
+pub use shaders::{MoonParams, RingParams};
+pub use pipeline::{render_mesh, FragmentOutput};
+
+/// How lighting is evaluated across each triangle
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShadingMode {
+    Procedural,  // procedural planet color, lit per fragment
+    Gouraud,     // lighting per vertex, interpolated
+    Flat,        // one color per triangle
+    VertexColor, // the mesh's own vertex colors (OBJ materials), interpolated and unlit
+    Checker,     // checker pattern over the texture coordinates, lit per fragment
+    Textured,    // the rocky planet takes its color from `Uniforms::color_map`, lit per fragment
+}
+
+impl ShadingMode {
+    pub fn next(self) -> Self {
+        match self {
+            ShadingMode::Procedural => ShadingMode::Gouraud,
+            ShadingMode::Gouraud => ShadingMode::Flat,
+            ShadingMode::Flat => ShadingMode::VertexColor,
+            ShadingMode::VertexColor => ShadingMode::Checker,
+            ShadingMode::Checker => ShadingMode::Textured,
+            ShadingMode::Textured => ShadingMode::Procedural,
+        }
+    }
+}
+
+/// How a planet surface responds to the lights. Each planet has its own
+/// (`shaders::planet_lighting_model`); `Uniforms::lighting_model` can force one for all
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LightingModel {
+    Lambert, // hard cut at the terminator, lit side capped at 1 (rocky, gaseous, ringed, lava)
+    Soft,    // the terminator fades over a band instead of a hard cut (bioluminescent)
+    Wrap,    // light wraps past the terminator, for a diffuse, cloudy look
+    Fresnel, // surfaces seen edge-on whiten, highlights above 1 kept for the tone mapper (ice)
+}
+
+impl LightingModel {
+    pub fn next(self) -> Self {
+        match self {
+            LightingModel::Lambert => LightingModel::Soft,
+            LightingModel::Soft => LightingModel::Wrap,
+            LightingModel::Wrap => LightingModel::Fresnel,
+            LightingModel::Fresnel => LightingModel::Lambert,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            LightingModel::Lambert => "lambert",
+            LightingModel::Soft => "terminador suave",
+            LightingModel::Wrap => "envolvente",
+            LightingModel::Fresnel => "fresnel",
+        }
+    }
+}
+
+/// Debug output that replaces the color of every pass (planet, clouds, rings, moons)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DebugView {
+    Off,           // normal shading
+    Normals,       // interpolated normal in the body's own coordinates, xyz mapped from [-1, 1] to RGB [0, 1]
+    WorldPosition, // the position the shaders sample, xyz mapped from [-DEBUG_POSITION_EXTENT, DEBUG_POSITION_EXTENT]
+}
+
+// Half-size of the cube that DebugView::WorldPosition maps to RGB. It covers the
+// widest rings; anything beyond it saturates.
+const DEBUG_POSITION_EXTENT: f32 = 3.5;
+
+impl DebugView {
+    pub fn next(self) -> Self {
+        match self {
+            DebugView::Off => DebugView::Normals,
+            DebugView::Normals => DebugView::WorldPosition,
+            DebugView::WorldPosition => DebugView::Off,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            DebugView::Off => "desactivada",
+            DebugView::Normals => "normales",
+            DebugView::WorldPosition => "posición",
+        }
+    }
+
+    /// Color shown for `fragment`, or None when the view is off
+    pub fn color(self, fragment: &Fragment) -> Option<Vector3> {
+        match self {
+            DebugView::Off => None,
+            DebugView::Normals => {
+                let n = fragment.normal;
+                Some(Vector3::new(n.x * 0.5 + 0.5, n.y * 0.5 + 0.5, n.z * 0.5 + 0.5))
+            }
+            // `world_position` is whatever each pass shades with: the planet's own (model)
+            // coordinates, the displaced ring and moon positions
+            DebugView::WorldPosition => {
+                let p = fragment.world_position * (0.5 / DEBUG_POSITION_EXTENT);
+                Some(Vector3::new(
+                    (p.x + 0.5).clamp(0.0, 1.0),
+                    (p.y + 0.5).clamp(0.0, 1.0),
+                    (p.z + 0.5).clamp(0.0, 1.0),
+                ))
+            }
+        }
+    }
+}
+
+/// Detail of the procedural noise: scales every `fractal_noise` octave count
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NoiseQuality {
+    Low,    // half the octaves, for weak machines
+    Medium, // the octave counts tuned per planet
+    High,   // 1.5x the octaves, for screenshots
+}
+
+impl NoiseQuality {
+    pub fn next(self) -> Self {
+        match self {
+            NoiseQuality::Low => NoiseQuality::Medium,
+            NoiseQuality::Medium => NoiseQuality::High,
+            NoiseQuality::High => NoiseQuality::Low,
+        }
+    }
+
+    /// Octave count to use instead of `base`, never below one
+    pub fn octaves(self, base: i32) -> i32 {
+        let factor = match self {
+            NoiseQuality::Low => 0.5,
+            NoiseQuality::Medium => 1.0,
+            NoiseQuality::High => 1.5,
+        };
+        ((base as f32 * factor).round() as i32).max(1)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            NoiseQuality::Low => "baja",
+            NoiseQuality::Medium => "media",
+            NoiseQuality::High => "alta",
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Uniforms {
+    pub model_matrix: Matrix,
+    pub view_matrix: Matrix,
+    pub projection_matrix: Matrix,
+    pub viewport_matrix: Matrix,
+    pub camera_position: Vector3, // en el mundo; cada pase la lleva a las coordenadas del cuerpo
+    pub time: f32,
+    pub dt: f32,
+    pub planet_type: i32,
+    pub render_type: i32,
+    pub shading_mode: ShadingMode,
+    pub lighting_model: Option<LightingModel>, // None: cada planeta usa el suyo (planet_lighting_model)
+    pub debug_view: DebugView, // vista de depuración: reemplaza el color de todos los pases
+    pub noise_quality: NoiseQuality,
+    pub seed: u32, // semilla del ruido: cada valor da otra variante del mismo tipo de planeta
+    pub craters: Vec<(Vector3, f32)>, // cráteres del planeta rocoso, de generate_craters(seed, ...)
+    pub ao_strength: f32, // oscurecimiento de valles, cráteres y grietas (planetas rocoso y de hielo), 0 = sin oclusión
+    pub moon: MoonParams, // luna activa cuando render_type == 2
+    pub rings: RingParams, // radios, inclinación y grosor de los anillos del planeta 3
+    pub ring_shadow_softness: f32, // ancho del borde de la sombra del planeta sobre los anillos
+    pub shadow_moons: Vec<MoonParams>, // lunas que pueden eclipsar al planeta (vacío: sin eclipses)
+    pub eclipse_softness: f32, // ancho de la penumbra de las lunas, en radios de la luna
+    pub rotation_speed: f32, // giro del planeta activo (radianes por segundo)
+    pub tone_mapping: bool, // comprimir el HDR con tone_map en vez de recortar en 1.0
+    pub color_map: Option<Arc<Texture>>, // mapa de color del planeta rocoso en ShadingMode::Textured
+    pub cull_backfaces: bool, // descartar los triángulos de espaldas a la cámara antes de rasterizar
+}
+
+/// Buffers reused by the render passes across frames. Each pass clears and refills
+/// them, so after the first frame the vertex and triangle storage is already
+/// allocated. Before, every pass of every frame allocated both from scratch
+/// (six growing Vecs per frame with the rocky planet's two moons, plus regrowth).
+#[derive(Default)]
+pub struct RenderScratch {
+    pub transformed_vertices: Vec<Vertex>,
+    pub triangles: Vec<[Vertex; 3]>,
+    // Triangles skipped for being off screen or facing away, summed over every pass of the frame
+    pub culled_triangles: usize,
+}
+
+impl RenderScratch {
+    /// Empties the buffers while keeping their capacity (the culled count is per frame, not per pass)
+    pub fn clear(&mut self) {
+        self.transformed_vertices.clear();
+        self.triangles.clear();
+    }
+}
+
+/// Draws the planet surface, shaded per fragment by `fragment_shader`
+pub fn render_planet(framebuffer: &mut Framebuffer, scratch: &mut RenderScratch, uniforms: &Uniforms, vertex_array: &[Vertex], lights: &[Light]) {
+    let mut planet_uniforms = uniforms.clone();
+    planet_uniforms.render_type = 0;
+    planet_uniforms.camera_position = object_space_point(&uniforms.camera_position, &uniforms.model_matrix);
+    let lights = &object_space_lights(lights, &uniforms.model_matrix);
+
+    render_mesh(framebuffer, scratch, &planet_uniforms, vertex_array, lights, |fragment| {
+        Some(FragmentOutput::Opaque(fragment_shader(fragment, &planet_uniforms, lights)))
+    });
+}
+
+/// Draws the active planet and whatever goes with it (clouds, rings or moons).
+/// Only touches the framebuffer, so it works the same with or without a window.
+pub fn render_scene(framebuffer: &mut Framebuffer, scratch: &mut RenderScratch, uniforms: &Uniforms, vertex_array: &[Vertex], lights: &[Light], moons: &[MoonParams]) {
+    // Eclipses: las lunas solo existen en el planeta 0, así que solo ahí proyectan sombra
+    if uniforms.planet_type == 0 {
+        let mut planet_uniforms = uniforms.clone();
+        planet_uniforms.shadow_moons = moons.to_vec();
+        render_planet(framebuffer, scratch, &planet_uniforms, vertex_array, lights);
+    } else {
+        render_planet(framebuffer, scratch, uniforms, vertex_array, lights);
+    }
+
+    // Capa de nubes SOLO para el planeta gaseoso
+    if uniforms.planet_type == 1 {
+        render_clouds(framebuffer, scratch, uniforms, vertex_array, lights);
+    }
+
+    // Anillos SOLO para planeta 3
+    if uniforms.planet_type == 3 {
+        render_rings(framebuffer, scratch, uniforms, vertex_array, lights);
+    }
+
+    // Lunas SOLO para planeta 0 (rocoso)
+    if uniforms.planet_type == 0 {
+        render_moon(framebuffer, scratch, uniforms, vertex_array, lights, moons);
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use matrix::{create_model_matrix, create_projection_matrix, create_viewport_matrix};
+    use shaders::{generate_craters, planet_rotation_speed};
+    use std::f32::consts::PI;
+
+    const SIZE: i32 = 128;
+
+    // The startup view, camera at (0, 0, 8) looking at the planet, without axial tilt
+    pub(crate) fn uniforms_for(planet_type: i32) -> Uniforms {
+        let camera = Camera::new(
+            Vector3::new(0.0, 0.0, 8.0),
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+        );
+        Uniforms {
+            model_matrix: create_model_matrix(Vector3::new(0.0, 0.0, 0.0), 1.0, Vector3::new(0.0, 0.0, 0.0)),
+            view_matrix: camera.get_view_matrix(),
+            projection_matrix: create_projection_matrix(PI / 3.0, 1.0, 0.1, 100.0),
+            viewport_matrix: create_viewport_matrix(0.0, 0.0, SIZE as f32, SIZE as f32),
+            camera_position: camera.get_position(),
+            time: 1.0,
+            dt: 0.0,
+            planet_type,
+            render_type: 0,
+            shading_mode: ShadingMode::Procedural,
+            lighting_model: None,
+            debug_view: DebugView::Off,
+            noise_quality: NoiseQuality::Medium,
+            seed: 0,
+            craters: generate_craters(0, 12),
+            ao_strength: 0.5,
+            moon: MoonParams::default(),
+            rings: RingParams::default(),
+            ring_shadow_softness: 0.08,
+            shadow_moons: Vec::new(),
+            eclipse_softness: 0.3,
+            rotation_speed: planet_rotation_speed(planet_type),
+            tone_mapping: false,
+            color_map: None,
+            cull_backfaces: true,
+        }
+    }
+
+    fn sphere() -> Vec<Vertex> {
+        Obj::load(concat!(env!("CARGO_MANIFEST_DIR"), "/models/sphere.obj")).expect("models/sphere.obj").get_vertex_array()
+    }
+
+    // Center pixel of the scene with the given moons
+    fn scene_center(vertices: &[Vertex], moons: &[MoonParams]) -> Color {
+        let mut framebuffer = Framebuffer::new(SIZE, SIZE);
+        framebuffer.clear();
+        render_scene(&mut framebuffer, &mut RenderScratch::default(), &uniforms_for(0), vertices, &Light::preset("key"), moons);
+        framebuffer.get_pixel(SIZE / 2, SIZE / 2).unwrap()
+    }
+
+    // Default moon on the view axis at time 1, behind the planet (z < 0) or in front of it.
+    // The phase puts it at x = 0, and without the wobble it stays at y = 0
+    fn moon_on_axis(behind: bool) -> MoonParams {
+        let moon = MoonParams::default();
+        let angle = if behind { 1.5 * PI } else { 0.5 * PI };
+        MoonParams { phase: angle - moon.speed, wobble: 0.0, ..moon }
+    }
+
+    #[test]
+    fn rocky_planet_renders_without_a_window() {
+        let mut framebuffer = Framebuffer::new(SIZE, SIZE);
+        framebuffer.clear();
+        render_planet(&mut framebuffer, &mut RenderScratch::default(), &uniforms_for(0), &sphere(), &Light::preset("key"));
+
+        let center = framebuffer.get_pixel(SIZE / 2, SIZE / 2).unwrap();
+        assert!(center.r > center.g && center.r > center.b, "center pixel {:?} is not reddish", center);
+    }
+
+    #[test]
+    fn planet_hides_the_moon_behind_it() {
+        let vertices = sphere();
+        let planet = scene_center(&vertices, &[]);
+
+        assert_eq!(scene_center(&vertices, &[moon_on_axis(true)]), planet);
+        // The same moon in front covers the center, so the check above can fail
+        assert_ne!(scene_center(&vertices, &[moon_on_axis(false)]), planet);
+    }
+}
@@ -1,11 +1,131 @@
+#![allow(dead_code)]
+
 use raylib::prelude::*;
 
+/// How a light falls off with distance
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LightKind {
+    /// No distance falloff, the same brightness everywhere
+    Directional,
+    /// Dims with distance as `1 / (1 + linear*d + quadratic*d^2)`
+    Point,
+}
+
+#[derive(Clone, Debug)]
 pub struct Light {
     pub position: Vector3,
+    /// Tint multiplied into the diffuse and specular light, per channel; white changes nothing
+    pub color: Vector3,
+    pub intensity: f32,
+    pub kind: LightKind,
+    pub linear: f32,
+    pub quadratic: f32,
+    /// Minimum lighting this light leaves on surfaces facing away from it
+    pub ambient: f32,
 }
 
 impl Light {
     pub fn new(position: Vector3) -> Self {
-        Light { position }
+        Light {
+            position,
+            color: Vector3::new(1.0, 1.0, 1.0),
+            intensity: 1.0,
+            kind: LightKind::Directional,
+            linear: 0.05,
+            quadratic: 0.01,
+            ambient: 0.1,
+        }
+    }
+
+    /// A named arrangement of lights around a planet at the origin, with the camera on +Z:
+    ///
+    /// - `"key"`: one white light up and to the right, the default look
+    /// - `"three-point"`: a white key light, a dim bluish fill from the other side and a
+    ///   back light behind the planet that outlines the silhouette
+    /// - `"rim"`: only a back light, so the planet is a dark disc with a bright edge
+    /// - `"binary"`: two suns on opposite sides, a white one and a dimmer red one, which
+    ///   leave a narrow night band in between
+    ///
+    /// The first light is always the key light. Unknown names give no lights.
+    pub fn preset(name: &str) -> Vec<Light> {
+        let light = |position: Vector3, color: Vector3, intensity: f32| {
+            let mut light = Light::new(position);
+            light.color = color;
+            light.intensity = intensity;
+            light
+        };
+        let white = Vector3::new(1.0, 1.0, 1.0);
+        match name {
+            // Direction (1, 1, 1) with intensity √3: what the shaders used before lights were configurable
+            "key" => vec![light(Vector3::new(5.0, 5.0, 5.0), white, 3.0_f32.sqrt())],
+            "three-point" => vec![
+                light(Vector3::new(5.0, 4.0, 6.0), white, 1.4),
+                light(Vector3::new(-6.0, 1.0, 4.0), Vector3::new(0.7, 0.8, 1.0), 0.4),
+                light(Vector3::new(0.0, 5.0, -7.0), white, 1.2),
+            ],
+            "rim" => vec![light(Vector3::new(0.0, 2.0, -8.0), Vector3::new(0.9, 0.95, 1.0), 2.0)],
+            "binary" => vec![
+                light(Vector3::new(7.0, 2.0, 3.0), white, 1.3),
+                light(Vector3::new(-7.0, -1.0, 2.0), Vector3::new(1.0, 0.45, 0.3), 0.9),
+            ],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Distance falloff factor at `point`: always 1.0 for directional lights
+    pub fn attenuation(&self, point: &Vector3) -> f32 {
+        match self.kind {
+            LightKind::Directional => 1.0,
+            LightKind::Point => {
+                let dx = self.position.x - point.x;
+                let dy = self.position.y - point.y;
+                let dz = self.position.z - point.z;
+                let d = (dx * dx + dy * dy + dz * dz).sqrt();
+                1.0 / (1.0 + self.linear * d + self.quadratic * d * d)
+            }
+        }
+    }
+
+    /// Unit vector from the origin towards the light (+Y when the light sits at the origin)
+    pub fn direction(&self) -> Vector3 {
+        let length = (self.position.x * self.position.x + self.position.y * self.position.y + self.position.z * self.position.z).sqrt();
+        if length > 0.0 {
+            Vector3::new(self.position.x / length, self.position.y / length, self.position.z / length)
+        } else {
+            Vector3::new(0.0, 1.0, 0.0)
+        }
+    }
+
+    /// Moves the light around the origin, keeping its distance: `yaw` turns around +Y
+    /// starting at +X towards +Z and `pitch` rises from the XZ plane, both in radians
+    pub fn set_direction(&mut self, yaw: f32, pitch: f32) {
+        let distance = (self.position.x * self.position.x + self.position.y * self.position.y + self.position.z * self.position.z).sqrt();
+        self.position = Vector3::new(
+            distance * pitch.cos() * yaw.cos(),
+            distance * pitch.sin(),
+            distance * pitch.cos() * yaw.sin(),
+        );
+    }
+
+    /// Normalized direction from `point` towards the light
+    pub fn direction_to(&self, point: &Vector3) -> Vector3 {
+        let mut dir = Vector3::new(
+            self.position.x - point.x,
+            self.position.y - point.y,
+            self.position.z - point.z,
+        );
+        let length = (dir.x * dir.x + dir.y * dir.y + dir.z * dir.z).sqrt();
+        if length > 0.0 {
+            dir.x /= length;
+            dir.y /= length;
+            dir.z /= length;
+        }
+        dir
     }
-}
\ No newline at end of file
+}
+
+/// Ambient floor of the scene: the largest `ambient` among the lights (0 without lights),
+/// kept in [0, 1]. Every shader uses it as the darkest a lit surface gets.
+pub fn ambient_light(lights: &[Light]) -> f32 {
+    lights.iter().map(|light| light.ambient).fold(0.0, f32::max).clamp(0.0, 1.0)
+}
@@ -1,79 +1,309 @@
 // main.rs
-mod framebuffer;
-mod triangle;
-mod obj;
-mod matrix;
-mod fragment;
-mod vertex;
-mod camera;
-mod shaders;
-mod light;
-
-use triangle::triangle;
-use obj::Obj;
-use framebuffer::Framebuffer;
+mod args;
+mod scene;
+
+use ship::obj::{Obj, WindingOrder};
+use ship::texture::Texture;
+use ship::framebuffer::Framebuffer;
 use raylib::prelude::*;
 use std::thread;
 use std::time::Duration;
 use std::f32::consts::PI;
-use matrix::{create_model_matrix, create_projection_matrix, create_viewport_matrix};
-use vertex::Vertex;
-use camera::Camera;
-use shaders::{vertex_shader, fragment_shader, render_rings, render_moon};
-use light::Light;
-
-#[derive(Clone)]
-pub struct Uniforms {
-    pub model_matrix: Matrix,
-    pub view_matrix: Matrix,
-    pub projection_matrix: Matrix,
-    pub viewport_matrix: Matrix,
-    pub time: f32,
-    pub dt: f32,
-    pub planet_type: i32,
-    pub render_type: i32,
+use std::sync::Arc;
+use ship::matrix::{create_model_matrix, create_projection_matrix, create_orthographic_matrix, create_viewport_matrix,
+                   create_body_matrix, multiply_matrix_vector4, quaternion_from_axis_angle, quaternion_multiply, quaternion_normalize};
+use ship::vertex::Vertex;
+use ship::camera::Camera;
+use ship::shaders::{generate_craters, planet_rotation_speed, planet_axial_tilt, planet_lighting_model, render_sun, MoonParams, RingParams};
+use ship::light::{Light, LightKind, ambient_light};
+use ship::{Uniforms, ShadingMode, LightingModel, DebugView, NoiseQuality, RenderScratch, render_scene};
+use args::Args;
+use scene::Scene;
+
+/// What the camera keeps centered
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CameraFocus {
+    Free,   // the user pans freely
+    Planet, // follows the planet center
+    Moon,   // follows the first moon along its orbit
+}
+
+/// Circular orbit around the origin in the XZ plane, the same path the moons follow
+#[derive(Clone, Copy, Debug)]
+struct Orbit {
+    radius: f32,
+    speed: f32, // radians per second of orbit time
+    phase: f32, // starting angle (radians)
 }
 
-fn render_planet(framebuffer: &mut Framebuffer, uniforms: &Uniforms, vertex_array: &[Vertex], light: &Light) {
-    let mut transformed_vertices = Vec::with_capacity(vertex_array.len());
-    let mut planet_uniforms = uniforms.clone();
-    planet_uniforms.render_type = 0;
-    
-    for vertex in vertex_array {
-        let transformed = vertex_shader(vertex, &planet_uniforms);
-        transformed_vertices.push(transformed);
+impl Orbit {
+    fn position(&self, time: f32) -> Vector3 {
+        let angle = time * self.speed + self.phase;
+        Vector3::new(self.radius * angle.cos(), 0.0, self.radius * angle.sin())
     }
+}
+
+/// One planet drawn in the scene, with its own place and size in the world
+#[derive(Clone, Copy, Debug)]
+struct CelestialBody {
+    planet_type: i32,
+    translation: Vector3,
+    scale: f32,
+    rotation_speed: f32,
+    orbit: Option<Orbit>, // None: stays put (the central star)
+}
 
-    let mut triangles = Vec::new();
-    for i in (0..transformed_vertices.len()).step_by(3) {
-        if i + 2 < transformed_vertices.len() {
-            triangles.push([
-                transformed_vertices[i].clone(),
-                transformed_vertices[i + 1].clone(),
-                transformed_vertices[i + 2].clone(),
-            ]);
+impl CameraFocus {
+    fn label(self) -> &'static str {
+        match self {
+            CameraFocus::Free => "libre",
+            CameraFocus::Planet => "planeta",
+            CameraFocus::Moon => "luna",
         }
     }
+}
+
+// Arreglos de luces con el teclado numérico 0 (nombre, preset de Light::preset); el
+// primero es el de siempre, una sola luz que ilumina igual que antes de tener varias
+const LIGHT_PRESETS: [(&str, &str); 4] = [
+    ("principal", "key"),
+    ("tres puntos", "three-point"),
+    ("contraluz", "rim"),
+    ("estrella binaria", "binary"),
+];
+
+fn default_lights() -> Vec<Light> {
+    Light::preset(LIGHT_PRESETS[0].1)
+}
+
+// Sistema solar (tecla 0): el planeta de lava hace de estrella en el origen y los demás
+// la orbitan (tipo, escala, órbita). Los radios dejan espacio a los anillos y las lunas
+const SYSTEM_BODIES: [(i32, f32, Option<Orbit>); PLANET_COUNT] = [
+    (5, 1.0, None),
+    (0, 0.35, Some(Orbit { radius: 3.2, speed: 0.5, phase: 0.0 })),
+    (2, 0.4, Some(Orbit { radius: 5.0, speed: 0.38, phase: 4.0 })),
+    (1, 0.6, Some(Orbit { radius: 6.2, speed: 0.22, phase: 2.0 })),
+    (3, 0.5, Some(Orbit { radius: 8.4, speed: 0.15, phase: 1.0 })),
+    (4, 0.35, Some(Orbit { radius: 10.4, speed: 0.1, phase: 3.0 })),
+];
+// Escala de la velocidad de todas las órbitas (teclado numérico * y /)
+const ORBIT_SPEED_STEP: f32 = 0.25;
+const MAX_ORBIT_SPEED: f32 = 4.0;
 
-    let mut fragments = Vec::new();
-    for tri in &triangles {
-        fragments.extend(triangle(&tri[0], &tri[1], &tri[2], light));
+// Cuerpos a dibujar en `orbit_time`: solo el planeta activo, o todos en el sistema solar
+fn celestial_bodies(planet_type: i32, solar_system: bool, rotation_speeds: &[f32], orbit_time: f32) -> Vec<CelestialBody> {
+    if !solar_system {
+        return vec![CelestialBody {
+            planet_type,
+            translation: Vector3::new(0.0, 0.0, 0.0),
+            scale: 1.0,
+            rotation_speed: rotation_speeds[planet_type as usize],
+            orbit: None,
+        }];
     }
+    SYSTEM_BODIES.iter().map(|&(planet_type, scale, orbit)| CelestialBody {
+        planet_type,
+        translation: orbit.map_or(Vector3::new(0.0, 0.0, 0.0), |orbit| orbit.position(orbit_time)),
+        scale,
+        rotation_speed: rotation_speeds[planet_type as usize],
+        orbit,
+    }).collect()
+}
+
+// Vista inclinada desde arriba para ver todas las órbitas
+fn system_camera(camera: &mut Camera) {
+    camera.set_view(
+        Vector3::new(0.0, 10.0, 18.0),
+        Vector3::new(0.0, 0.0, 0.0),
+        Vector3::new(0.0, 1.0, 0.0),
+    );
+}
+
+// Dos lunas con fases opuestas para que no se encimen
+fn default_moons() -> [MoonParams; 2] {
+    [
+        MoonParams::default(),
+        MoonParams {
+            orbit_radius: 3.8,
+            speed: 0.25,
+            inclination: 0.35,
+            scale: 0.15,
+            phase: PI,
+            wobble: 0.2,
+        },
+    ]
+}
+
+fn default_camera() -> Camera {
+    Camera::new(
+        Vector3::new(0.0, 0.0, 8.0),
+        Vector3::new(0.0, 0.0, 0.0),
+        Vector3::new(0.0, 1.0, 0.0),
+    )
+}
 
-    for fragment in fragments {      
-        let final_color = fragment_shader(&fragment, uniforms);
-        framebuffer.point(
-            fragment.position.x as i32,
-            fragment.position.y as i32,
-            final_color,
-            fragment.depth,
-        );
+/// Renders one frame of `args.planet` at time 0 with the default camera and lights
+/// and writes it to `args.out`. No window or draw handle is created.
+fn render_headless(args: &Args, vertex_array: &[Vertex]) -> Result<(), std::io::Error> {
+    let mut framebuffer = Framebuffer::new(args.width, args.height);
+    framebuffer.set_background_color(BACKGROUND_COLORS[0].1);
+    framebuffer.clear();
+    framebuffer.render_starfield(STAR_SEED, STAR_DENSITY, 0.0);
+
+    let camera = default_camera();
+    let rotation = Vector3::new(0.0, 0.0, planet_axial_tilt(args.planet).to_radians());
+    let aspect = args.width as f32 / args.height as f32;
+    let uniforms = Uniforms {
+        model_matrix: create_model_matrix(Vector3::new(0.0, 0.0, 0.0), 1.0, rotation),
+        view_matrix: camera.get_view_matrix(),
+        projection_matrix: create_projection_matrix(FIELD_OF_VIEW, aspect, args.near, args.far),
+        viewport_matrix: create_viewport_matrix(0.0, 0.0, framebuffer.width as f32, framebuffer.height as f32),
+        camera_position: camera.get_position(),
+        time: 0.0,
+        dt: 0.0,
+        planet_type: args.planet,
+        render_type: 0,
+        shading_mode: ShadingMode::Procedural,
+        lighting_model: None,
+        debug_view: DebugView::Off,
+        noise_quality: NoiseQuality::Medium,
+        seed: 0,
+        craters: generate_craters(0, CRATER_COUNT),
+        ao_strength: AO_STRENGTH,
+        moon: MoonParams::default(),
+        rings: RingParams::default(),
+        ring_shadow_softness: RING_SHADOW_SOFTNESS,
+        shadow_moons: Vec::new(),
+        eclipse_softness: ECLIPSE_SOFTNESS,
+        tone_mapping: false,
+        rotation_speed: planet_rotation_speed(args.planet),
+        color_map: None,
+        cull_backfaces: true,
+    };
+
+    let mut scratch = RenderScratch::default();
+    render_scene(&mut framebuffer, &mut scratch, &uniforms, vertex_array, &default_lights(), &default_moons());
+    // PPM sin dependencias si se pide esa extensión, PNG en cualquier otro caso
+    if args.out.to_lowercase().ends_with(".ppm") {
+        framebuffer.save_ppm(&args.out)
+    } else {
+        framebuffer.save_png(&args.out)
     }
 }
 
+// Paso fijo de la animación al avanzar cuadro a cuadro en pausa
+const STEP_DT: f32 = 1.0 / 60.0;
+// Velocidad de giro del modelo con las flechas (radianes por segundo)
+const MODEL_ROTATION_SPEED: f32 = 1.5;
+// Multiplicador global del tiempo de animación: paso de [ y ] y tope
+const TIME_SCALE_STEP: f32 = 0.25;
+const MAX_TIME_SCALE: f32 = 8.0;
+// Bloom: luminancia mínima que brilla y radio del desenfoque en píxeles de pantalla.
+// El umbral es bajo para que las plantas del lado nocturno del planeta 2 alcancen a brillar
+const BLOOM_THRESHOLD: f32 = 0.2;
+const BLOOM_RADIUS: i32 = 6;
+const RING_SHADOW_SOFTNESS: f32 = 0.08;
+// Luz ambiente: paso de las teclas 7 y 8 (se aplica a todas las luces)
+const AMBIENT_STEP: f32 = 0.05;
+// Ancho de la penumbra de los eclipses, en radios de la luna
+const ECLIPSE_SOFTNESS: f32 = 0.3;
+// Colores del sol con F2; con la rojiza el planeta de hielo se ve rosado del lado iluminado
+const SUN_COLORS: [(&str, [f32; 3]); 3] = [
+    ("blanca", [1.0, 1.0, 1.0]),
+    ("naranja", [1.0, 0.7, 0.4]),
+    ("rojiza", [1.0, 0.45, 0.4]),
+];
+// Colores de fondo con la tecla º; clear() pinta con el elegido en cada cuadro
+const BACKGROUND_COLORS: [(&str, Color); 4] = [
+    ("gris oscuro", Color::new(30, 30, 30, 255)),
+    ("negro", Color::new(0, 0, 0, 255)),       // para tomas del espacio
+    ("azul noche", Color::new(8, 12, 32, 255)),
+    ("blanco", Color::new(255, 255, 255, 255)), // para presentaciones (sin estrellas a la vista)
+];
+// Cantidad de cráteres del planeta rocoso
+const CRATER_COUNT: usize = 12;
+// Oscurecimiento máximo de valles, cráteres y grietas (tecla Y)
+const AO_STRENGTH: f32 = 0.5;
+// Cielo de estrellas: la semilla fija el patrón y la densidad es la fracción de píxeles con estrella
+const STAR_SEED: u32 = 1337;
+const STAR_DENSITY: f32 = 0.002;
+const GAMMA: f32 = 2.2;
+// Contorno: salto mínimo de profundidad (Sobel sobre z NDC) que se considera borde
+const OUTLINE_THRESHOLD: f32 = 0.01;
+// Viñeta: cuánto se oscurecen las esquinas y radio (0 centro, 1 esquina) donde empieza
+const VIGNETTE_STRENGTH: f32 = 0.6;
+const VIGNETTE_RADIUS: f32 = 0.45;
+// Niveles por canal de la paleta reducida (F12): 8, 64 y 216 colores, o sin reducir
+const PALETTE_LEVELS: [Option<u8>; 4] = [None, Some(2), Some(4), Some(6)];
+const ROTATION_SPEED_STEP: f32 = 0.05;
+const MAX_ROTATION_SPEED: f32 = 5.0;
+const TILT_STEP: f32 = 5.0; // grados
+// Anillos del planeta 3 (F8): los clásicos son los de siempre
+const RING_STYLES: [(&str, RingParams); 3] = [
+    ("clásicos", RingParams { inner: 1.6, outer: 2.4, tilt: 0.0, thickness: 0.05, shadow_softness: 0.04 }),
+    ("delgados", RingParams { inner: 2.1, outer: 2.3, tilt: 0.0, thickness: 0.02, shadow_softness: 0.04 }),
+    ("anchos", RingParams { inner: 1.3, outer: 3.4, tilt: 0.0, thickness: 0.08, shadow_softness: 0.04 }),
+];
+// Inclinación de los anillos sobre el ecuador (teclado numérico 1 y 3), en grados
+const MAX_RING_TILT: f32 = 60.0;
+// Giro de la luz con I/J/K/L (radianes por segundo)
+const LIGHT_ROTATION_SPEED: f32 = 1.2;
+// Radio de la esfera que marca dónde está la luz (F7)
+const SUN_SPRITE_RADIUS: f32 = 0.2;
+const PLANET_COUNT: usize = 6;
+// Campo de visión vertical de la proyección en perspectiva (el inicial; - y = lo cambian)
+const FIELD_OF_VIEW: f32 = PI / 3.0;
+// Límites y paso del campo de visión, en grados; fuera de ellos la proyección se deforma o degenera
+const MIN_FOV_DEGREES: f32 = 20.0;
+const MAX_FOV_DEGREES: f32 = 120.0;
+const FOV_STEP_DEGREES: f32 = 5.0;
+// Insert/Supr y Inicio/Fin multiplican o dividen los planos de recorte por este factor.
+// El plano cercano no baja de MIN_NEAR: acercarlo a 0 gasta la precisión de profundidad
+// (ver create_projection_matrix) y las superficies lejanas empiezan a parpadear
+const CLIP_PLANE_FACTOR: f32 = 2.0;
+const MIN_NEAR: f32 = 0.01;
+const MAX_FAR: f32 = 10000.0;
+const SCENE_FILE: &str = "scene.json";
+// Grabación (Enter): RECORD_FRAMES cuadros numerados en RECORD_DIR, avanzando el tiempo
+// RECORD_DT por cuadro y dando una vuelta completa con la cámara
+const RECORD_DIR: &str = "frames";
+const RECORD_FRAMES: u32 = 120;
+const RECORD_DT: f32 = 1.0 / 30.0;
+// Anillos de la esfera generada cuando falta sphere.obj (con el doble de sectores)
+const DEFAULT_SPHERE_RINGS: u32 = 32;
+
 fn main() {
-    let window_width = 1300;
-    let window_height = 900;
+    let args = Args::parse();
+    let window_width = args.width;
+    let window_height = args.height;
+
+    // Esfera generada con --sphere, o la de sphere.obj (generada también si no se puede leer)
+    let obj = match args.sphere_rings {
+        Some(rings) => Obj::uv_sphere(rings, rings * 2),
+        None => match Obj::load("./models/sphere.obj") {
+            Ok(obj) => obj,
+            Err(e) => {
+                eprintln!("No se pudo cargar ./models/sphere.obj ({}), se usa una esfera generada", e);
+                Obj::uv_sphere(DEFAULT_SPHERE_RINGS, DEFAULT_SPHERE_RINGS * 2)
+            }
+        },
+    };
+    println!("Modelo: {}", obj.stats());
+    let mut vertex_array = obj.get_vertex_array();
+    // Modelo actual y el orden de sus caras, para recargarlo invertido con F11
+    let mut model_path = "./models/sphere.obj".to_string();
+    let mut winding = WindingOrder::CounterClockwise;
+
+    if args.headless {
+        match render_headless(&args, &vertex_array) {
+            Ok(()) => println!("Imagen guardada en {}", args.out),
+            Err(e) => {
+                eprintln!("No se pudo guardar {}: {}", args.out, e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
 
     let (mut window, raylib_thread) = raylib::init()
         .size(window_width, window_height)
@@ -82,67 +312,591 @@ fn main() {
         .build();
 
     let mut framebuffer = Framebuffer::new(window_width, window_height);
-    let mut camera = Camera::new(
-        Vector3::new(0.0, 0.0, 8.0),
-        Vector3::new(0.0, 0.0, 0.0),
-        Vector3::new(0.0, 1.0, 0.0),
-    );
+    let mut camera = default_camera();
 
-    let translation = Vector3::new(0.0, 0.0, 0.0);
-    let scale = 1.0;
-    let rotation = Vector3::new(0.0, 0.0, 0.0);
-    let light = Light::new(Vector3::new(5.0, 5.0, 5.0));
+    // Orientación acumulada del planeta (cuaternión x, y, z, w)
+    let mut orientation = Vector4::new(0.0, 0.0, 0.0, 1.0);
+    let mut lights = default_lights();
+    // Dirección de la luz en ángulos, partiendo de donde está la luz por defecto
+    let light_direction = lights[0].direction();
+    let mut light_yaw = light_direction.z.atan2(light_direction.x);
+    let mut light_pitch = light_direction.y.asin();
+    let mut scratch = RenderScratch::default();
+    // Mapa de color para el modo texturizado (G); sin él el planeta rocoso sigue procedural
+    let mut color_map = args.texture.as_deref().and_then(|path| match Texture::load(path) {
+        Ok(texture) => Some(Arc::new(texture)),
+        Err(e) => {
+            eprintln!("No se pudo cargar la textura {}: {}", path, e);
+            None
+        }
+    });
 
-    let obj = Obj::load("./models/sphere.obj").expect("Failed to load sphere.obj");
-    let vertex_array = obj.get_vertex_array();
+    framebuffer.set_background_color(BACKGROUND_COLORS[0].1);
 
-    framebuffer.set_background_color(Color::new(30, 30, 30, 255));
+    let moons = default_moons();
 
     let mut time = 0.0;
-    let mut planet_type = 0;
+    let mut paused = false;
+    let mut time_scale: f32 = 1.0;
+    let mut planet_type = args.planet;
+    let mut show_depth = false;
+    let mut orthographic = false;
+    let mut shading_mode = ShadingMode::Procedural;
+    let mut debug_view = DebugView::Off;
+    // None: cada planeta con su modelo de iluminación
+    let mut lighting_model: Option<LightingModel> = None;
+    let mut noise_quality = NoiseQuality::Medium;
+    let mut seed: u32 = 0;
+    let mut craters = generate_craters(seed, CRATER_COUNT);
+    let mut show_overlay = false;
+    let mut bloom = false;
+    let mut starfield = true;
+    let mut background = 0;
+    let mut gamma_correction = false;
+    let mut tone_mapping = false;
+    let mut outline = false;
+    let mut vignette = false;
+    let mut dither = false;
+    let mut ambient_occlusion = true;
+    // Cuadros ya guardados de la grabación en curso
+    let mut recording: Option<u32> = None;
+    let mut palette = 0;
+    let mut cull_backfaces = true;
+    let mut depth_test = true;
+    let mut camera_focus = CameraFocus::Free;
+    let mut sun_color = 0;
+    let mut light_preset = 0;
+    let mut show_sun = true;
+    let mut ring_style = 0;
+    let mut ring_tilt: f32 = 0.0;
+    let mut solar_system = false;
+    let mut field_of_view = FIELD_OF_VIEW;
+    let mut near = args.near;
+    let mut far = args.far;
+    // Tiempo propio de las órbitas: cambiar su velocidad no hace saltar a los planetas
+    let mut orbit_time = 0.0;
+    let mut orbit_speed: f32 = 1.0;
+    // Cada planeta recuerda su propia velocidad de giro, partiendo de la base
+    let mut rotation_speeds: Vec<f32> = (0..PLANET_COUNT as i32).map(planet_rotation_speed).collect();
+    let mut axial_tilts: Vec<f32> = (0..PLANET_COUNT as i32).map(planet_axial_tilt).collect();
 
     while !window.window_should_close() {
-        let dt = window.get_frame_time();
+        if window.is_key_pressed(KeyboardKey::KEY_SPACE) { paused = !paused; }
+
+        // [ y ] frenan o aceleran la animación (0 la detiene sin pausar)
+        if window.is_key_pressed(KeyboardKey::KEY_LEFT_BRACKET) {
+            time_scale = (time_scale - TIME_SCALE_STEP).clamp(0.0, MAX_TIME_SCALE);
+        }
+        if window.is_key_pressed(KeyboardKey::KEY_RIGHT_BRACKET) {
+            time_scale = (time_scale + TIME_SCALE_STEP).clamp(0.0, MAX_TIME_SCALE);
+        }
+
+        if window.is_key_pressed(KeyboardKey::KEY_ENTER) {
+            recording = match recording {
+                Some(frames) => {
+                    println!("Grabación detenida: {} cuadros en {}/", frames, RECORD_DIR);
+                    None
+                }
+                None => match std::fs::create_dir_all(RECORD_DIR) {
+                    Ok(()) => {
+                        println!("Grabando {} cuadros en {}/", RECORD_FRAMES, RECORD_DIR);
+                        Some(0)
+                    }
+                    Err(e) => {
+                        eprintln!("No se pudo crear {}: {}", RECORD_DIR, e);
+                        None
+                    }
+                },
+            };
+        }
+
+        // En pausa el tiempo no avanza (dt = 0) salvo al dar un paso con la tecla punto.
+        // Al grabar el paso es fijo (y no se detiene en pausa): el clip sale igual de
+        // fluido sin importar los FPS reales
+        let dt = if recording.is_some() {
+            RECORD_DT
+        } else if !paused {
+            window.get_frame_time() * time_scale
+        } else if window.is_key_pressed(KeyboardKey::KEY_PERIOD) {
+            STEP_DT
+        } else {
+            0.0
+        };
         time += dt;
-        
+        orbit_time += dt * orbit_speed;
+
         if window.is_key_pressed(KeyboardKey::KEY_ONE) { planet_type = 0; }
         if window.is_key_pressed(KeyboardKey::KEY_TWO) { planet_type = 1; }
         if window.is_key_pressed(KeyboardKey::KEY_THREE) { planet_type = 2; }
         if window.is_key_pressed(KeyboardKey::KEY_FOUR) { planet_type = 3; }
         if window.is_key_pressed(KeyboardKey::KEY_FIVE) { planet_type = 4; }
+        if window.is_key_pressed(KeyboardKey::KEY_SIX) { planet_type = 5; }
+        if window.is_key_pressed(KeyboardKey::KEY_ZERO) {
+            solar_system = !solar_system;
+            if solar_system { system_camera(&mut camera); } else { camera.reset(); }
+            camera_focus = CameraFocus::Free;
+        }
+        let fov_delta = if window.is_key_pressed(KeyboardKey::KEY_EQUAL) {
+            FOV_STEP_DEGREES
+        } else if window.is_key_pressed(KeyboardKey::KEY_MINUS) {
+            -FOV_STEP_DEGREES
+        } else {
+            0.0
+        };
+        if fov_delta != 0.0 {
+            let degrees = (field_of_view.to_degrees() + fov_delta).clamp(MIN_FOV_DEGREES, MAX_FOV_DEGREES);
+            field_of_view = degrees.to_radians();
+        }
+        // El cercano siempre queda por debajo de la mitad del lejano
+        if window.is_key_pressed(KeyboardKey::KEY_INSERT) {
+            near = (near * CLIP_PLANE_FACTOR).min(far / CLIP_PLANE_FACTOR);
+        }
+        if window.is_key_pressed(KeyboardKey::KEY_DELETE) {
+            near = (near / CLIP_PLANE_FACTOR).max(MIN_NEAR);
+        }
+        if window.is_key_pressed(KeyboardKey::KEY_END) {
+            far = (far * CLIP_PLANE_FACTOR).min(MAX_FAR);
+        }
+        if window.is_key_pressed(KeyboardKey::KEY_HOME) {
+            far = (far / CLIP_PLANE_FACTOR).max(near * CLIP_PLANE_FACTOR);
+        }
+        if window.is_key_pressed(KeyboardKey::KEY_KP_MULTIPLY) {
+            orbit_speed = (orbit_speed + ORBIT_SPEED_STEP).min(MAX_ORBIT_SPEED);
+        }
+        if window.is_key_pressed(KeyboardKey::KEY_KP_DIVIDE) {
+            orbit_speed = (orbit_speed - ORBIT_SPEED_STEP).max(0.0);
+        }
+        if window.is_key_pressed(KeyboardKey::KEY_D) { show_depth = !show_depth; }
+        if window.is_key_pressed(KeyboardKey::KEY_O) { orthographic = !orthographic; }
+        if window.is_key_pressed(KeyboardKey::KEY_R) {
+            camera.reset();
+            camera_focus = CameraFocus::Free;
+        }
+        if window.is_key_pressed(KeyboardKey::KEY_U) {
+            camera_focus = if camera_focus == CameraFocus::Moon { CameraFocus::Planet } else { CameraFocus::Moon };
+        }
+        if window.is_key_pressed(KeyboardKey::KEY_G) { shading_mode = shading_mode.next(); }
+        if window.is_key_pressed(KeyboardKey::KEY_COMMA) { debug_view = debug_view.next(); }
+        if window.is_key_pressed(KeyboardKey::KEY_Q) { noise_quality = noise_quality.next(); }
+        if window.is_key_pressed(KeyboardKey::KEY_APOSTROPHE) {
+            // El de cada planeta -> lambert -> terminador suave -> envolvente -> fresnel -> el de cada planeta
+            lighting_model = match lighting_model {
+                None => Some(LightingModel::Lambert),
+                Some(LightingModel::Fresnel) => None,
+                Some(model) => Some(model.next()),
+            };
+        }
+        let ambient_delta = if window.is_key_pressed(KeyboardKey::KEY_EIGHT) {
+            AMBIENT_STEP
+        } else if window.is_key_pressed(KeyboardKey::KEY_SEVEN) {
+            -AMBIENT_STEP
+        } else {
+            0.0
+        };
+        if ambient_delta != 0.0 {
+            let ambient = (ambient_light(&lights) + ambient_delta).clamp(0.0, 1.0);
+            for light in lights.iter_mut() {
+                light.ambient = ambient;
+            }
+        }
+        if window.is_key_pressed(KeyboardKey::KEY_TAB) {
+            seed = seed.wrapping_add(1);
+            craters = generate_craters(seed, CRATER_COUNT);
+        }
+        if window.is_key_pressed(KeyboardKey::KEY_F) { show_overlay = !show_overlay; }
+        if window.is_key_pressed(KeyboardKey::KEY_B) { bloom = !bloom; }
+        if window.is_key_pressed(KeyboardKey::KEY_N) { starfield = !starfield; }
+        if window.is_key_pressed(KeyboardKey::KEY_GRAVE) {
+            background = (background + 1) % BACKGROUND_COLORS.len();
+            framebuffer.set_background_color(BACKGROUND_COLORS[background].1);
+        }
+        // Con Mayús las flechas arriba / abajo giran el planeta en vez de cambiar la velocidad
+        let shift = window.is_key_down(KeyboardKey::KEY_LEFT_SHIFT) || window.is_key_down(KeyboardKey::KEY_RIGHT_SHIFT);
+        if !shift && window.is_key_pressed(KeyboardKey::KEY_UP) {
+            let speed = &mut rotation_speeds[planet_type as usize];
+            *speed = (*speed + ROTATION_SPEED_STEP).clamp(-MAX_ROTATION_SPEED, MAX_ROTATION_SPEED);
+        }
+        if !shift && window.is_key_pressed(KeyboardKey::KEY_DOWN) {
+            let speed = &mut rotation_speeds[planet_type as usize];
+            *speed = (*speed - ROTATION_SPEED_STEP).clamp(-MAX_ROTATION_SPEED, MAX_ROTATION_SPEED);
+        }
+        if window.is_key_pressed(KeyboardKey::KEY_KP_9) {
+            let tilt = &mut axial_tilts[planet_type as usize];
+            *tilt = (*tilt + TILT_STEP).clamp(-180.0, 180.0);
+        }
+        if window.is_key_pressed(KeyboardKey::KEY_KP_7) {
+            let tilt = &mut axial_tilts[planet_type as usize];
+            *tilt = (*tilt - TILT_STEP).clamp(-180.0, 180.0);
+        }
+        // Guardar / cargar la escena
+        if window.is_key_pressed(KeyboardKey::KEY_S) {
+            let scene = Scene {
+                planet_type,
+                camera_eye: [camera.eye.x, camera.eye.y, camera.eye.z],
+                camera_target: [camera.target.x, camera.target.y, camera.target.z],
+                camera_up: [camera.up.x, camera.up.y, camera.up.z],
+                rotation_speeds: rotation_speeds.clone(),
+                axial_tilts: axial_tilts.clone(),
+                time_scale,
+            };
+            match scene.save(SCENE_FILE) {
+                Ok(()) => println!("Escena guardada en {}", SCENE_FILE),
+                Err(e) => eprintln!("No se pudo guardar {}: {}", SCENE_FILE, e),
+            }
+        }
+        if window.is_key_pressed(KeyboardKey::KEY_L) {
+            match Scene::load(SCENE_FILE, PLANET_COUNT) {
+                Ok(scene) => {
+                    planet_type = scene.planet_type;
+                    let [ex, ey, ez] = scene.camera_eye;
+                    let [tx, ty, tz] = scene.camera_target;
+                    let [ux, uy, uz] = scene.camera_up;
+                    camera.set_view(Vector3::new(ex, ey, ez), Vector3::new(tx, ty, tz), Vector3::new(ux, uy, uz));
+                    // Mismos límites que las teclas
+                    rotation_speeds = scene.rotation_speeds.iter().map(|speed| speed.clamp(-MAX_ROTATION_SPEED, MAX_ROTATION_SPEED)).collect();
+                    axial_tilts = scene.axial_tilts.iter().map(|tilt| tilt.clamp(-180.0, 180.0)).collect();
+                    time_scale = scene.time_scale.min(MAX_TIME_SCALE);
+                    println!("Escena cargada de {}", SCENE_FILE);
+                }
+                Err(e) => eprintln!("No se pudo cargar {}: {}", SCENE_FILE, e),
+            }
+        }
+        if window.is_key_pressed(KeyboardKey::KEY_X) {
+            outline = !outline;
+            framebuffer.set_outline(if outline { Some((OUTLINE_THRESHOLD, Color::new(10, 10, 10, 255))) } else { None });
+        }
+        if window.is_key_pressed(KeyboardKey::KEY_H) { tone_mapping = !tone_mapping; }
+        // Para ver el efecto de cada etapa: sin las dos, la cara trasera se ve a través
+        if window.is_key_pressed(KeyboardKey::KEY_F4) { cull_backfaces = !cull_backfaces; }
+        if window.is_key_pressed(KeyboardKey::KEY_F6) {
+            depth_test = !depth_test;
+            framebuffer.set_depth_test(depth_test);
+        }
+        if window.is_key_pressed(KeyboardKey::KEY_F3) {
+            vignette = !vignette;
+            framebuffer.set_vignette(if vignette { Some((VIGNETTE_STRENGTH, VIGNETTE_RADIUS)) } else { None });
+        }
+        if window.is_key_pressed(KeyboardKey::KEY_Y) { ambient_occlusion = !ambient_occlusion; }
+        if window.is_key_pressed(KeyboardKey::KEY_F10) {
+            dither = !dither;
+            framebuffer.set_dither(dither);
+        }
+        if window.is_key_pressed(KeyboardKey::KEY_F12) {
+            palette = (palette + 1) % PALETTE_LEVELS.len();
+            framebuffer.set_quantize(PALETTE_LEVELS[palette]);
+            match PALETTE_LEVELS[palette] {
+                Some(levels) => println!("Paleta: {} niveles por canal ({} colores)", levels, (levels as u32).pow(3)),
+                None => println!("Paleta: color completo"),
+            }
+        }
+        if window.is_key_pressed(KeyboardKey::KEY_V) {
+            gamma_correction = !gamma_correction;
+            framebuffer.set_gamma(if gamma_correction { Some(GAMMA) } else { None });
+        }
+        if window.is_key_pressed(KeyboardKey::KEY_T) {
+            // Luz direccional (sin atenuación) <-> luz puntual que se debilita con la distancia
+            for light in lights.iter_mut() {
+                light.kind = match light.kind {
+                    LightKind::Directional => LightKind::Point,
+                    LightKind::Point => LightKind::Directional,
+                };
+            }
+        }
+        // Mover la luz alrededor del planeta: J/Ñ la giran, I/K la suben y la bajan.
+        // En teclados en español la Ñ está donde el punto y coma
+        let light_step = LIGHT_ROTATION_SPEED * window.get_frame_time();
+        let mut light_moved = false;
+        if window.is_key_down(KeyboardKey::KEY_J) { light_yaw -= light_step; light_moved = true; }
+        if window.is_key_down(KeyboardKey::KEY_SEMICOLON) { light_yaw += light_step; light_moved = true; }
+        if window.is_key_down(KeyboardKey::KEY_I) { light_pitch += light_step; light_moved = true; }
+        if window.is_key_down(KeyboardKey::KEY_K) { light_pitch -= light_step; light_moved = true; }
+        if light_moved {
+            light_pitch = light_pitch.clamp(-PI / 2.0 + 0.05, PI / 2.0 - 0.05);
+            // Solo la luz principal: las demás del preset se quedan donde están
+            lights[0].set_direction(light_yaw, light_pitch);
+        }
+        if window.is_key_pressed(KeyboardKey::KEY_F2) {
+            sun_color = (sun_color + 1) % SUN_COLORS.len();
+            let [r, g, b] = SUN_COLORS[sun_color].1;
+            lights[0].color = Vector3::new(r, g, b);
+        }
+        if window.is_key_pressed(KeyboardKey::KEY_KP_0) {
+            // Otro arreglo de luces; se conservan el tipo de luz (T) y la luz ambiente (7 / 8),
+            // y la principal vuelve a ser blanca como en todos los presets
+            light_preset = (light_preset + 1) % LIGHT_PRESETS.len();
+            let kind = lights[0].kind;
+            let ambient = ambient_light(&lights);
+            lights = Light::preset(LIGHT_PRESETS[light_preset].1);
+            for light in lights.iter_mut() {
+                light.kind = kind;
+                light.ambient = ambient;
+            }
+            sun_color = 0;
+            let direction = lights[0].direction();
+            light_yaw = direction.z.atan2(direction.x);
+            light_pitch = direction.y.asin();
+        }
+        if window.is_key_pressed(KeyboardKey::KEY_F7) { show_sun = !show_sun; }
+        if window.is_key_pressed(KeyboardKey::KEY_F8) { ring_style = (ring_style + 1) % RING_STYLES.len(); }
+        if window.is_key_pressed(KeyboardKey::KEY_KP_1) {
+            ring_tilt = (ring_tilt - TILT_STEP).max(-MAX_RING_TILT);
+        }
+        if window.is_key_pressed(KeyboardKey::KEY_KP_3) {
+            ring_tilt = (ring_tilt + TILT_STEP).min(MAX_RING_TILT);
+        }
+        if window.is_key_pressed(KeyboardKey::KEY_M) {
+            // Supermuestreo 1x -> 2x -> 4x
+            let factor = match framebuffer.supersample() { 1 => 2, 2 => 4, _ => 1 };
+            framebuffer.set_supersample(factor);
+            println!("Supermuestreo {}x", factor);
+        }
         
+        // Cargar un .obj (modelo) o .png (mapa de color) arrastrado a la ventana;
+        // si falla se conserva el actual
+        if window.is_file_dropped() {
+            let dropped = window.load_dropped_files();
+            for path in dropped.paths() {
+                if path.to_lowercase().ends_with(".png") {
+                    match Texture::load(path) {
+                        Ok(texture) => {
+                            println!("Textura cargada: {} ({}x{})", path, texture.width, texture.height);
+                            color_map = Some(Arc::new(texture));
+                        }
+                        Err(e) => eprintln!("No se pudo cargar {}: {}", path, e),
+                    }
+                    continue;
+                }
+                if !path.to_lowercase().ends_with(".obj") {
+                    eprintln!("Ignorando {}: solo se aceptan archivos .obj y .png", path);
+                    continue;
+                }
+                match Obj::load(path) {
+                    Ok(obj) => {
+                        let stats = obj.stats();
+                        vertex_array = obj.get_vertex_array();
+                        model_path = path.to_string();
+                        winding = WindingOrder::CounterClockwise;
+                        println!("Modelo cargado: {} ({})", path, stats);
+                        // Encuadrar el modelo nuevo, sea del tamaño que sea
+                        camera.frame_bounds(stats.min, stats.max, field_of_view);
+                        camera_focus = CameraFocus::Free;
+                    }
+                    Err(e) => eprintln!("No se pudo cargar {}: {}", path, e),
+                }
+            }
+        }
+
+        // Modelo al revés (caras en sentido horario): recargarlo con las caras invertidas
+        if window.is_key_pressed(KeyboardKey::KEY_F11) {
+            match Obj::load_with_options(&model_path, winding.flipped()) {
+                Ok(obj) => {
+                    vertex_array = obj.get_vertex_array();
+                    winding = winding.flipped();
+                    println!("Caras de {} en sentido {}", model_path, winding.label());
+                }
+                Err(e) => eprintln!("No se pudo recargar {}: {}", model_path, e),
+            }
+        }
+
+        // Girar el planeta con las flechas: izquierda / derecha, y Mayús + arriba / abajo
+        // (no depende de la pausa)
+        let spin = MODEL_ROTATION_SPEED * window.get_frame_time();
+        let mut spin_axis = Vector3::new(0.0, 0.0, 0.0);
+        if window.is_key_down(KeyboardKey::KEY_LEFT) { spin_axis.y -= 1.0; }
+        if window.is_key_down(KeyboardKey::KEY_RIGHT) { spin_axis.y += 1.0; }
+        if shift && window.is_key_down(KeyboardKey::KEY_UP) { spin_axis.x -= 1.0; }
+        if shift && window.is_key_down(KeyboardKey::KEY_DOWN) { spin_axis.x += 1.0; }
+        if spin_axis.x != 0.0 || spin_axis.y != 0.0 {
+            let delta = quaternion_from_axis_angle(spin_axis, spin);
+            orientation = quaternion_normalize(quaternion_multiply(delta, orientation));
+        }
+
         camera.process_input(&window);
+        if recording.is_some() {
+            camera.orbit(2.0 * PI / RECORD_FRAMES as f32, 0.0);
+        }
         framebuffer.clear();
+        if starfield {
+            framebuffer.render_starfield(STAR_SEED, STAR_DENSITY, time);
+        }
+        scratch.culled_triangles = 0;
 
-        let model_matrix = create_model_matrix(translation, scale, rotation);
+        // La inclinación axial gira el eje Y del objeto (eje de giro y normal de los anillos)
+        // alrededor de Z, así que el planeta y sus anillos se inclinan con la misma matriz
+        let model_matrix_for = |body: &CelestialBody| {
+            let tilt = axial_tilts[body.planet_type as usize].to_radians();
+            let rotation = Vector3::new(0.0, 0.0, tilt);
+            create_body_matrix(body.translation, body.scale, rotation, orientation)
+        };
+        let mut bodies = celestial_bodies(planet_type, solar_system, &rotation_speeds, orbit_time);
+
+        // Seguimiento: las lunas solo se dibujan en el planeta 0, en los demás se sigue al planeta
+        let focus_point = match camera_focus {
+            CameraFocus::Free => None,
+            CameraFocus::Moon if planet_type == 0 => Some(moons[0].position(time)),
+            _ => Some(Vector3::new(0.0, 0.0, 0.0)),
+        };
+        let active_body = bodies.iter().find(|body| body.planet_type == planet_type);
+        if let (Some(point), Some(body)) = (focus_point, active_body) {
+            let world = multiply_matrix_vector4(&model_matrix_for(body), &Vector4::new(point.x, point.y, point.z, 1.0));
+            camera.track(Vector3::new(world.x, world.y, world.z));
+        }
         let view_matrix = camera.get_view_matrix();
-        let projection_matrix = create_projection_matrix(PI / 3.0, window_width as f32 / window_height as f32, 0.1, 100.0);
-        let viewport_matrix = create_viewport_matrix(0.0, 0.0, window_width as f32, window_height as f32);
+        let aspect = window_width as f32 / window_height as f32;
+        let projection_matrix = if orthographic {
+            // Vista fija de 4 unidades de alto: el planeta no cambia de tamaño con la distancia
+            let half_height = 2.0;
+            create_orthographic_matrix(-half_height * aspect, half_height * aspect, -half_height, half_height, near, far)
+        } else {
+            create_projection_matrix(field_of_view, aspect, near, far)
+        };
+        // El viewport usa la resolución interna (incluye el supermuestreo)
+        let viewport_matrix = create_viewport_matrix(0.0, 0.0, framebuffer.width as f32, framebuffer.height as f32);
 
-        let planet_uniforms = Uniforms {
-            model_matrix,
+        let base_uniforms = Uniforms {
+            model_matrix: Matrix::identity(),
             view_matrix,
             projection_matrix,
             viewport_matrix,
+            camera_position: camera.get_position(),
             time,
             dt,
             planet_type,
             render_type: 0,
+            shading_mode,
+            lighting_model,
+            debug_view,
+            noise_quality,
+            seed,
+            craters: craters.clone(),
+            ao_strength: if ambient_occlusion { AO_STRENGTH } else { 0.0 },
+            moon: MoonParams::default(),
+            rings: RingParams { tilt: ring_tilt.to_radians(), ..RING_STYLES[ring_style].1 },
+            ring_shadow_softness: RING_SHADOW_SOFTNESS,
+            shadow_moons: Vec::new(),
+            eclipse_softness: ECLIPSE_SOFTNESS,
+            tone_mapping,
+            color_map: color_map.clone(),
+            cull_backfaces,
+            rotation_speed: 0.0,
+        };
+
+        // Todos comparten el buffer de profundidad, así que los sólidos se tapan bien en
+        // cualquier orden; se dibujan de atrás hacia adelante (se reordena cada cuadro porque
+        // las órbitas los cruzan) para que los anillos y las nubes, que mezclan sin escribir
+        // profundidad, queden sobre lo que tienen detrás
+        let eye = camera.get_position();
+        let distance_to_eye = |body: &CelestialBody| {
+            let d = body.translation - eye;
+            d.x * d.x + d.y * d.y + d.z * d.z
         };
+        bodies.sort_by(|a, b| distance_to_eye(b).total_cmp(&distance_to_eye(a)));
 
-        render_planet(&mut framebuffer, &planet_uniforms, &vertex_array, &light);
+        // Las luces están en el mundo (cada pase las lleva a las coordenadas de su cuerpo),
+        // así que el sol va justo en su posición. Se dibuja antes que los cuerpos para que
+        // los anillos y nubes lo cubran; en el sistema solar la estrella ya marca dónde está la luz
+        if show_sun && !solar_system {
+            for light in &lights {
+                render_sun(&mut framebuffer, &mut scratch, &base_uniforms, &vertex_array, light.position, SUN_SPRITE_RADIUS, light.color);
+            }
+        }
+        // Centro de la estrella en el mundo: el origen de su matriz de modelo
+        let star_center = bodies.iter().find(|body| body.orbit.is_none()).map(|star| {
+            let center = multiply_matrix_vector4(&model_matrix_for(star), &Vector4::new(0.0, 0.0, 0.0, 1.0));
+            Vector3::new(center.x, center.y, center.z)
+        });
+        for body in &bodies {
+            let mut body_uniforms = base_uniforms.clone();
+            body_uniforms.model_matrix = model_matrix_for(body);
+            body_uniforms.planet_type = body.planet_type;
+            body_uniforms.rotation_speed = body.rotation_speed;
+            // A los que orbitan los ilumina la estrella: la luz va en su centro, en el mundo, y
+            // cada pase la lleva a las coordenadas del cuerpo con su matriz de modelo
+            let body_lights: Vec<Light> = match star_center {
+                Some(center) if body.orbit.is_some() => lights.iter().map(|light| {
+                    let mut light = light.clone();
+                    light.position = center;
+                    light
+                }).collect(),
+                _ => lights.clone(),
+            };
+            render_scene(&mut framebuffer, &mut scratch, &body_uniforms, &vertex_array, &body_lights, &moons);
+        }
+
+        if show_depth {
+            framebuffer.show_depth();
+        } else if bloom {
+            // El radio se escala con el supermuestreo para verse igual en pantalla
+            framebuffer.apply_bloom(BLOOM_THRESHOLD, BLOOM_RADIUS * framebuffer.supersample());
+        }
 
-        // Anillos SOLO para planeta 3
-        if planet_type == 3 {
-            render_rings(&mut framebuffer, &planet_uniforms, &vertex_array, &light);
+        if window.is_key_pressed(KeyboardKey::KEY_P) {
+            let filename = format!("planet_{}_{:.2}.png", planet_type, time);
+            match framebuffer.save_png(&filename) {
+                Ok(()) => println!("Captura guardada en {}", filename),
+                Err(e) => eprintln!("No se pudo guardar {}: {}", filename, e),
+            }
         }
 
-        // Luna SOLO para planeta 0 (rocoso)
-        if planet_type == 0 {
-            render_moon(&mut framebuffer, &planet_uniforms, &vertex_array, &light);
+        if let Some(frames) = recording {
+            let filename = format!("{}/frame_{:04}.png", RECORD_DIR, frames);
+            if let Err(e) = framebuffer.save_png(&filename) {
+                eprintln!("No se pudo guardar {}: {}", filename, e);
+            }
+            recording = if frames + 1 < RECORD_FRAMES {
+                Some(frames + 1)
+            } else {
+                println!("Grabación terminada: {} cuadros en {}/", RECORD_FRAMES, RECORD_DIR);
+                None
+            };
         }
 
-        framebuffer.swap_buffers(&mut window, &raylib_thread);
+        // Sobredibujo: fragmentos sombreados de más por quedar detrás de otros
+        let fragments = framebuffer.fragment_stats();
+        let light_direction = lights[0].direction();
+        let overlay = if show_overlay {
+            vec![
+                format!("FPS: {}", window.get_fps()),
+                format!("Frame: {:.2} ms", window.get_frame_time() * 1000.0),
+                format!("Planeta: {}", planet_type),
+                if solar_system { format!("Órbitas: {:.2}x", orbit_speed) } else { "Órbitas: un solo planeta".to_string() },
+                format!("Cámara: {}", camera_focus.label()),
+                format!("Campo de visión: {:.0}°", field_of_view.to_degrees()),
+                format!("Planos de recorte: cerca {:.2}, lejos {:.0}", near, far),
+                format!("Ruido: calidad {}, semilla {}", noise_quality.label(), seed),
+                match lighting_model {
+                    Some(model) => format!("Iluminación: {} (todos los planetas)", model.label()),
+                    None => format!("Iluminación: {} (la del planeta)", planet_lighting_model(planet_type).label()),
+                },
+                format!("Ambiente: {:.2}", ambient_light(&lights)),
+                format!("Fondo: {}", BACKGROUND_COLORS[background].0),
+                format!("Luces: {} ({})", LIGHT_PRESETS[light_preset].0, lights.len()),
+                format!("Luz: {}, dirección ({:.2}, {:.2}, {:.2})", SUN_COLORS[sun_color].0, light_direction.x, light_direction.y, light_direction.z),
+                format!("Giro: {:.2} rad/s", rotation_speeds[planet_type as usize]),
+                format!("Inclinación: {:.0}°", axial_tilts[planet_type as usize]),
+                format!("Anillos: {}, inclinados {:.0}°", RING_STYLES[ring_style].0, ring_tilt),
+                format!("Tiempo: {:.2}{}", time, if paused { " (pausa)" } else { "" }),
+                format!("Escala de tiempo: {:.2}x", time_scale),
+                format!("Triángulos descartados: {}", scratch.culled_triangles),
+                format!(
+                    "Caras traseras: {}, profundidad: {}",
+                    if cull_backfaces { "descartadas" } else { "dibujadas" },
+                    if depth_test { "activada" } else { "desactivada" },
+                ),
+                format!("Fragmentos: {} (pasan {}, tapados {})", fragments.fragments, fragments.passed, fragments.rejected),
+                format!("Depuración: {}", debug_view.label()),
+                match recording {
+                    Some(frames) => format!("Grabando: {}/{}", frames, RECORD_FRAMES),
+                    None => "Grabación: detenida".to_string(),
+                },
+                if gamma_correction { format!("Gamma: {:.1}", GAMMA) } else { "Gamma: desactivada".to_string() },
+                format!("Tone mapping: {}", if tone_mapping { "ACES" } else { "recorte" }),
+            ]
+        } else {
+            Vec::new()
+        };
+
+        framebuffer.swap_buffers(&mut window, &raylib_thread, &overlay);
         thread::sleep(Duration::from_millis(16));
     }
 }
\ No newline at end of file
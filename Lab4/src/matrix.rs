@@ -49,7 +49,68 @@ pub fn new_matrix3(
     )
 }
 
-/// Creates a model matrix combining translation, scale, and rotation
+/// Inverts a 4x4 matrix by cofactor expansion. Returns None when the matrix is singular
+/// (determinant close to zero), e.g. a model matrix with a zero scale.
+pub fn invert(m: &Matrix) -> Option<Matrix> {
+    let a = [
+        m.m0, m.m1, m.m2, m.m3, m.m4, m.m5, m.m6, m.m7,
+        m.m8, m.m9, m.m10, m.m11, m.m12, m.m13, m.m14, m.m15,
+    ];
+
+    // 2x2 minors of the first two and last two columns
+    let s0 = a[0] * a[5] - a[4] * a[1];
+    let s1 = a[0] * a[6] - a[4] * a[2];
+    let s2 = a[0] * a[7] - a[4] * a[3];
+    let s3 = a[1] * a[6] - a[5] * a[2];
+    let s4 = a[1] * a[7] - a[5] * a[3];
+    let s5 = a[2] * a[7] - a[6] * a[3];
+
+    let c5 = a[10] * a[15] - a[14] * a[11];
+    let c4 = a[9] * a[15] - a[13] * a[11];
+    let c3 = a[9] * a[14] - a[13] * a[10];
+    let c2 = a[8] * a[15] - a[12] * a[11];
+    let c1 = a[8] * a[14] - a[12] * a[10];
+    let c0 = a[8] * a[13] - a[12] * a[9];
+
+    let det = s0 * c5 - s1 * c4 + s2 * c3 + s3 * c2 - s4 * c1 + s5 * c0;
+    if det.abs() < 1e-8 {
+        return None;
+    }
+    let inv = 1.0 / det;
+
+    Some(Matrix {
+        m0: (a[5] * c5 - a[6] * c4 + a[7] * c3) * inv,
+        m1: (-a[1] * c5 + a[2] * c4 - a[3] * c3) * inv,
+        m2: (a[13] * s5 - a[14] * s4 + a[15] * s3) * inv,
+        m3: (-a[9] * s5 + a[10] * s4 - a[11] * s3) * inv,
+        m4: (-a[4] * c5 + a[6] * c2 - a[7] * c1) * inv,
+        m5: (a[0] * c5 - a[2] * c2 + a[3] * c1) * inv,
+        m6: (-a[12] * s5 + a[14] * s2 - a[15] * s1) * inv,
+        m7: (a[8] * s5 - a[10] * s2 + a[11] * s1) * inv,
+        m8: (a[4] * c4 - a[5] * c2 + a[7] * c0) * inv,
+        m9: (-a[0] * c4 + a[1] * c2 - a[3] * c0) * inv,
+        m10: (a[12] * s4 - a[13] * s2 + a[15] * s0) * inv,
+        m11: (-a[8] * s4 + a[9] * s2 - a[11] * s0) * inv,
+        m12: (-a[4] * c3 + a[5] * c1 - a[6] * c0) * inv,
+        m13: (a[0] * c3 - a[1] * c1 + a[2] * c0) * inv,
+        m14: (-a[12] * s3 + a[13] * s1 - a[14] * s0) * inv,
+        m15: (a[8] * s3 - a[9] * s1 + a[10] * s0) * inv,
+    })
+}
+
+/// Normal matrix for `model`: the transpose of its inverse. Under non-uniform scale the
+/// model matrix itself would tilt normals off their surface. Falls back to `model` when it
+/// is singular. The shaders light in object space and don't need it; it is for callers
+/// that shade their own meshes in world space.
+pub fn normal_matrix(model: &Matrix) -> Matrix {
+    match invert(model) {
+        Some(inverse) => inverse.transposed(),
+        None => *model,
+    }
+}
+
+/// Creates a model matrix combining translation, scale, and rotation: points are scaled,
+/// then rotated, then translated, so `translation` is where the object's origin ends up
 pub fn create_model_matrix(translation: Vector3, scale: f32, rotation: Vector3) -> Matrix {
     let (sin_x, cos_x) = rotation.x.sin_cos();
     let (sin_y, cos_y) = rotation.y.sin_cos();
@@ -97,12 +158,67 @@ pub fn create_model_matrix(translation: Vector3, scale: f32, rotation: Vector3)
         0.0, 0.0, 0.0, 1.0
     );
 
-    scale_matrix * translation_matrix * rotation_matrix 
+    // raylib's `a * b` applies a first, then b
+    scale_matrix * rotation_matrix * translation_matrix
+}
+
+/// Creates the model matrix of a body: scaled, tilted by the Euler angles in `rotation`,
+/// turned by the `orientation` quaternion and only then moved to `translation`, so
+/// turning the body never swings it away from its place
+pub fn create_body_matrix(translation: Vector3, scale: f32, rotation: Vector3, orientation: Vector4) -> Matrix {
+    let origin = Vector3::new(0.0, 0.0, 0.0);
+    create_model_matrix(origin, scale, rotation)
+        * create_rotation_from_quaternion(orientation)
+        * create_model_matrix(translation, 1.0, origin)
+}
+
+/// Creates a unit quaternion (x, y, z, w) rotating `angle` radians around `axis`
+pub fn quaternion_from_axis_angle(axis: Vector3, angle: f32) -> Vector4 {
+    let length = (axis.x * axis.x + axis.y * axis.y + axis.z * axis.z).sqrt();
+    if length == 0.0 {
+        return Vector4::new(0.0, 0.0, 0.0, 1.0);
+    }
+    let (sin_half, cos_half) = (angle / 2.0).sin_cos();
+    let s = sin_half / length;
+    Vector4::new(axis.x * s, axis.y * s, axis.z * s, cos_half)
 }
 
-/// Creates a view matrix using camera position, target, and up vector
-/// This implements a lookAt matrix for camera transformations
-pub fn create_view_matrix(eye: Vector3, target: Vector3, up: Vector3) -> Matrix {
+/// Hamilton product a * b: applying the result rotates by b first, then by a
+pub fn quaternion_multiply(a: Vector4, b: Vector4) -> Vector4 {
+    Vector4::new(
+        a.w * b.x + a.x * b.w + a.y * b.z - a.z * b.y,
+        a.w * b.y - a.x * b.z + a.y * b.w + a.z * b.x,
+        a.w * b.z + a.x * b.y - a.y * b.x + a.z * b.w,
+        a.w * b.w - a.x * b.x - a.y * b.y - a.z * b.z,
+    )
+}
+
+/// Normalizes a quaternion so accumulated rotations don't drift away from unit length
+pub fn quaternion_normalize(q: Vector4) -> Vector4 {
+    let length = (q.x * q.x + q.y * q.y + q.z * q.z + q.w * q.w).sqrt();
+    if length == 0.0 {
+        return Vector4::new(0.0, 0.0, 0.0, 1.0);
+    }
+    Vector4::new(q.x / length, q.y / length, q.z / length, q.w / length)
+}
+
+/// Creates a rotation matrix from a unit quaternion (x, y, z, w)
+pub fn create_rotation_from_quaternion(q: Vector4) -> Matrix {
+    let (x, y, z, w) = (q.x, q.y, q.z, q.w);
+
+    new_matrix4(
+        1.0 - 2.0 * (y * y + z * z), 2.0 * (x * y - z * w),       2.0 * (x * z + y * w),       0.0,
+        2.0 * (x * y + z * w),       1.0 - 2.0 * (x * x + z * z), 2.0 * (y * z - x * w),       0.0,
+        2.0 * (x * z - y * w),       2.0 * (y * z + x * w),       1.0 - 2.0 * (x * x + y * y), 0.0,
+        0.0,                         0.0,                         0.0,                         1.0,
+    )
+}
+
+/// Creates a right-handed lookAt view matrix: the camera sits at `eye`, looks down -Z
+/// toward `target`, with `up` giving the roll. Rows are (right, up, -forward).
+/// If `up` is parallel to the view direction another axis is used, so tracking a body
+/// straight above or below the camera doesn't produce NaNs.
+pub fn create_look_at(eye: Vector3, target: Vector3, up: Vector3) -> Matrix {
     // Calculate forward vector (from eye to target, normalized)
     let mut forward = Vector3::new(
         target.x - eye.x,
@@ -116,13 +232,20 @@ pub fn create_view_matrix(eye: Vector3, target: Vector3, up: Vector3) -> Matrix
     forward.z /= forward_length;
 
     // Calculate right vector (cross product of forward and up, normalized)
-    let mut right = Vector3::new(
+    let cross_up = |up: Vector3| Vector3::new(
         forward.y * up.z - forward.z * up.y,
         forward.z * up.x - forward.x * up.z,
         forward.x * up.y - forward.y * up.x,
     );
+    let mut right = cross_up(up);
+    let mut right_length = (right.x * right.x + right.y * right.y + right.z * right.z).sqrt();
+    if right_length < 1e-6 {
+        // Up is parallel to forward: fall back to whichever world axis is least aligned
+        let fallback = if forward.y.abs() < 0.9 { Vector3::new(0.0, 1.0, 0.0) } else { Vector3::new(0.0, 0.0, 1.0) };
+        right = cross_up(fallback);
+        right_length = (right.x * right.x + right.y * right.y + right.z * right.z).sqrt();
+    }
     // Normalize right
-    let right_length = (right.x * right.x + right.y * right.y + right.z * right.z).sqrt();
     right.x /= right_length;
     right.y /= right_length;
     right.z /= right_length;
@@ -147,8 +270,14 @@ pub fn create_view_matrix(eye: Vector3, target: Vector3, up: Vector3) -> Matrix
 /// Creates a perspective projection matrix
 /// fov_y: Field of view in radians (vertical)
 /// aspect: Aspect ratio (width / height)
-/// near: Near clipping plane distance
-/// far: Far clipping plane distance
+/// near: Near clipping plane distance, must be > 0
+/// far: Far clipping plane distance, must be > near
+///
+/// Any `near < far` keeps the depth order, since NDC z grows with distance. But z is
+/// hyperbolic in the view distance: about half of the [-1, 1] range is spent between
+/// `near` and `2 * near`. A very small near plane leaves little f32 precision for distant
+/// surfaces, and close ones start to z-fight. The ratio `far / near` matters more than `far`
+/// alone, so keep near as large as the scene allows.
 pub fn create_projection_matrix(fov_y: f32, aspect: f32, near: f32, far: f32) -> Matrix {
     let tan_half_fov = (fov_y / 2.0).tan();
 
@@ -160,6 +289,18 @@ pub fn create_projection_matrix(fov_y: f32, aspect: f32, near: f32, far: f32) ->
     )
 }
 
+/// Creates an orthographic projection matrix
+/// left, right, bottom, top: View volume bounds in view space
+/// near, far: Clipping plane distances, mapped to NDC z in [-1, 1] like the perspective matrix
+pub fn create_orthographic_matrix(left: f32, right: f32, bottom: f32, top: f32, near: f32, far: f32) -> Matrix {
+    new_matrix4(
+        2.0 / (right - left), 0.0, 0.0, -(right + left) / (right - left),
+        0.0, 2.0 / (top - bottom), 0.0, -(top + bottom) / (top - bottom),
+        0.0, 0.0, -2.0 / (far - near), -(far + near) / (far - near),
+        0.0, 0.0, 0.0, 1.0,
+    )
+}
+
 /// Creates a viewport matrix to transform NDC coordinates to screen space
 /// x, y: Viewport position (typically 0, 0)
 /// width, height: Viewport dimensions in pixels
@@ -173,4 +314,110 @@ pub fn create_viewport_matrix(x: f32, y: f32, width: f32, height: f32) -> Matrix
         0.0, 0.0, 1.0, 0.0,
         0.0, 0.0, 0.0, 1.0,
     )
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_matrix_near(a: &Matrix, b: &Matrix) {
+        let a = [a.m0, a.m1, a.m2, a.m3, a.m4, a.m5, a.m6, a.m7, a.m8, a.m9, a.m10, a.m11, a.m12, a.m13, a.m14, a.m15];
+        let b = [b.m0, b.m1, b.m2, b.m3, b.m4, b.m5, b.m6, b.m7, b.m8, b.m9, b.m10, b.m11, b.m12, b.m13, b.m14, b.m15];
+        for (x, y) in a.iter().zip(b.iter()) {
+            assert!((x - y).abs() < 1e-5, "{:?} != {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn matrix_times_its_inverse_is_identity() {
+        let model = create_model_matrix(Vector3::new(1.0, -2.0, 3.0), 1.5, Vector3::new(0.3, 0.5, 0.7));
+        let inverse = invert(&model).unwrap();
+        assert_matrix_near(&(model * inverse), &Matrix::identity());
+        assert_matrix_near(&(inverse * model), &Matrix::identity());
+    }
+
+    #[test]
+    fn body_matrix_translates_last() {
+        let translation = Vector3::new(4.0, -1.0, 2.5);
+        let orientation = quaternion_from_axis_angle(Vector3::new(0.0, 1.0, 0.0), 1.2);
+        let model = create_body_matrix(translation, 0.35, Vector3::new(0.0, 0.0, 25f32.to_radians()), orientation);
+
+        let origin = multiply_matrix_vector4(&model, &Vector4::new(0.0, 0.0, 0.0, 1.0));
+        assert!((origin.x - translation.x).abs() < 1e-5, "{:?}", origin);
+        assert!((origin.y - translation.y).abs() < 1e-5, "{:?}", origin);
+        assert!((origin.z - translation.z).abs() < 1e-5, "{:?}", origin);
+
+        // A point on the unit sphere stays 0.35 away from the body's center
+        let point = multiply_matrix_vector4(&model, &Vector4::new(1.0, 0.0, 0.0, 1.0));
+        let d = Vector3::new(point.x - translation.x, point.y - translation.y, point.z - translation.z);
+        assert!(((d.x * d.x + d.y * d.y + d.z * d.z).sqrt() - 0.35).abs() < 1e-5);
+    }
+
+    #[test]
+    fn zero_scale_is_not_invertible() {
+        assert!(invert(&create_model_matrix(Vector3::new(1.0, 0.0, 0.0), 0.0, Vector3::new(0.0, 0.0, 0.0))).is_none());
+    }
+
+    fn transform_direction(direction: Vector3, matrix: &Matrix) -> Vector3 {
+        let v = multiply_matrix_vector4(matrix, &Vector4::new(direction.x, direction.y, direction.z, 0.0));
+        let len = (v.x * v.x + v.y * v.y + v.z * v.z).sqrt();
+        Vector3::new(v.x / len, v.y / len, v.z / len)
+    }
+
+    #[test]
+    fn normals_stay_perpendicular_under_non_uniform_scale() {
+        // Scale x2 along X only: the plane x + y = 0 tilts and its normal has to follow
+        let model = new_matrix3(
+            2.0, 0.0, 0.0,
+            0.0, 1.0, 0.0,
+            0.0, 0.0, 1.0,
+        );
+        let tangent = transform_direction(Vector3::new(1.0, -1.0, 0.0), &model);
+        let normal = transform_direction(Vector3::new(1.0, 1.0, 0.0), &normal_matrix(&model));
+        let dot = tangent.x * normal.x + tangent.y * normal.y + tangent.z * normal.z;
+        assert!(dot.abs() < 1e-5, "dot = {}", dot);
+
+        // The model matrix alone would leave the normal off the perpendicular
+        let naive = transform_direction(Vector3::new(1.0, 1.0, 0.0), &model);
+        assert!((tangent.x * naive.x + tangent.y * naive.y).abs() > 0.1);
+    }
+
+    #[test]
+    fn look_at_basis_is_orthonormal() {
+        let view = create_look_at(Vector3::new(3.0, 4.0, 5.0), Vector3::new(-1.0, 0.5, 2.0), Vector3::new(0.0, 1.0, 0.0));
+        let rows = [
+            Vector3::new(view.m0, view.m4, view.m8),
+            Vector3::new(view.m1, view.m5, view.m9),
+            Vector3::new(view.m2, view.m6, view.m10),
+        ];
+        for (i, a) in rows.iter().enumerate() {
+            for (j, b) in rows.iter().enumerate() {
+                let dot = a.x * b.x + a.y * b.y + a.z * b.z;
+                let expected = if i == j { 1.0 } else { 0.0 };
+                assert!((dot - expected).abs() < 1e-5, "rows {} and {}: {}", i, j, dot);
+            }
+        }
+    }
+
+    #[test]
+    fn look_at_moves_the_eye_to_the_origin() {
+        let eye = Vector3::new(3.0, 4.0, 5.0);
+        let target = Vector3::new(-1.0, 0.5, 2.0);
+        let view = create_look_at(eye, target, Vector3::new(0.0, 1.0, 0.0));
+
+        let eye_view = multiply_matrix_vector4(&view, &Vector4::new(eye.x, eye.y, eye.z, 1.0));
+        assert!(eye_view.x.abs() < 1e-5 && eye_view.y.abs() < 1e-5 && eye_view.z.abs() < 1e-5);
+
+        // The target ends up straight ahead, down -Z
+        let target_view = multiply_matrix_vector4(&view, &Vector4::new(target.x, target.y, target.z, 1.0));
+        assert!(target_view.x.abs() < 1e-5 && target_view.y.abs() < 1e-5);
+        assert!(target_view.z < 0.0);
+    }
+
+    #[test]
+    fn quarter_turn_quaternion_matches_the_euler_matrix() {
+        let angle = std::f32::consts::FRAC_PI_2;
+        let from_quaternion = create_rotation_from_quaternion(quaternion_from_axis_angle(Vector3::new(0.0, 1.0, 0.0), angle));
+        let from_euler = create_model_matrix(Vector3::new(0.0, 0.0, 0.0), 1.0, Vector3::new(0.0, angle, 0.0));
+        assert_matrix_near(&from_quaternion, &from_euler);
+    }
+}
@@ -1,55 +1,429 @@
 // obj.rs
+#![allow(dead_code)]
+
 use crate::vertex::Vertex;
 use raylib::math::{Vector2, Vector3};
-use tobj;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+/// Why an `.obj` file could not be loaded
+#[derive(Debug)]
+pub enum ObjError {
+    /// The file could not be opened or read
+    IoError(std::io::Error),
+    /// A malformed line; `line` is 1-based
+    ParseError { line: usize, reason: String },
+    /// The file parsed but lacks something the renderer needs (vertices or faces)
+    MissingData(String),
+}
+
+impl fmt::Display for ObjError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ObjError::IoError(e) => write!(f, "no se pudo leer el archivo: {}", e),
+            ObjError::ParseError { line, reason } => write!(f, "línea {}: {}", line, reason),
+            ObjError::MissingData(what) => write!(f, "faltan datos: {}", what),
+        }
+    }
+}
+
+impl std::error::Error for ObjError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ObjError::IoError(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ObjError {
+    fn from(e: std::io::Error) -> Self {
+        ObjError::IoError(e)
+    }
+}
+
+/// Size of a loaded mesh, from `Obj::stats`
+#[derive(Clone, Copy, Debug)]
+pub struct MeshStats {
+    /// Distinct vertices after the loader merged repeated `v/vt/vn` corners
+    pub vertex_count: usize,
+    pub triangle_count: usize,
+    /// Corners of the axis-aligned bounding box of the vertex positions
+    pub min: Vector3,
+    pub max: Vector3,
+}
+
+impl fmt::Display for MeshStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} vértices, {} triángulos, caja ({:.2}, {:.2}, {:.2}) a ({:.2}, {:.2}, {:.2})",
+            self.vertex_count, self.triangle_count,
+            self.min.x, self.min.y, self.min.z,
+            self.max.x, self.max.y, self.max.z,
+        )
+    }
+}
+
+/// Order in which a face lists its corners when seen from the front. The OBJ format
+/// specifies counter-clockwise, which is the default and what back-face culling assumes.
+/// Some exporters write clockwise faces; loading those as `Clockwise` reverses every
+/// face, so culling keeps the outside instead of showing the model inside-out.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WindingOrder {
+    #[default]
+    CounterClockwise,
+    Clockwise,
+}
+
+impl WindingOrder {
+    pub fn flipped(self) -> Self {
+        match self {
+            WindingOrder::CounterClockwise => WindingOrder::Clockwise,
+            WindingOrder::Clockwise => WindingOrder::CounterClockwise,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            WindingOrder::CounterClockwise => "antihorario",
+            WindingOrder::Clockwise => "horario",
+        }
+    }
+}
 
 pub struct Obj {
     pub vertices: Vec<Vertex>,
     pub indices: Vec<u32>,
+    materials: HashMap<String, Vector3>,
 }
 
 impl Obj {
-    pub fn load(path: &str) -> Result<Self, tobj::LoadError> {
-        let (models, _materials) = tobj::load_obj(path, &tobj::GPU_LOAD_OPTIONS)?;
+    /// Loads a file with counter-clockwise faces, the OBJ default
+    pub fn load(path: &str) -> Result<Self, ObjError> {
+        Self::load_with_options(path, WindingOrder::CounterClockwise)
+    }
+
+    /// Loads a file whose faces follow `winding`. Clockwise faces are stored reversed,
+    /// so the mesh always ends up counter-clockwise.
+    pub fn load_with_options(path: &str, winding: WindingOrder) -> Result<Self, ObjError> {
+        let source = std::fs::read_to_string(path)?;
+        let base_dir = Path::new(path).parent().unwrap_or(Path::new(""));
+        Self::parse_with_options(&source, base_dir, winding)
+    }
+
+    /// Parses the text of an `.obj` file with counter-clockwise faces
+    pub fn parse(source: &str, base_dir: &Path) -> Result<Self, ObjError> {
+        Self::parse_with_options(source, base_dir, WindingOrder::CounterClockwise)
+    }
+
+    /// Parses the text of an `.obj` file. `mtllib` files are looked up relative to `base_dir`.
+    /// Faces are triangulated as a fan and every distinct `v/vt/vn` combination (per
+    /// material) becomes one vertex, so `indices` always describes triangles.
+    /// With `WindingOrder::Clockwise` each triangle is reversed. The normals computed for
+    /// files without `vn` follow the corrected order; `vn` normals are kept as written.
+    pub fn parse_with_options(source: &str, base_dir: &Path, winding: WindingOrder) -> Result<Self, ObjError> {
+        let white = Vector3::new(1.0, 1.0, 1.0);
+        let mut positions: Vec<Vector3> = Vec::new();
+        let mut normals: Vec<Vector3> = Vec::new();
+        let mut tex_coords: Vec<Vector2> = Vec::new();
+        let mut material_colors = HashMap::new();
+        let mut current_color = white;
 
         let mut vertices = Vec::new();
         let mut indices = Vec::new();
+        let mut has_normals = true;
+        let mut unique: HashMap<(FaceVertex, [u32; 3]), u32> = HashMap::new();
+
+        for (number, raw_line) in source.lines().enumerate() {
+            let line = number + 1;
+            let content = raw_line.split('#').next().unwrap_or("").trim();
+            let mut parts = content.split_whitespace();
+            let keyword = match parts.next() {
+                Some(keyword) => keyword,
+                None => continue,
+            };
+
+            match keyword {
+                "v" => positions.push(parse_vector3(&mut parts, line, "v")?),
+                "vn" => normals.push(parse_vector3(&mut parts, line, "vn")?),
+                "vt" => {
+                    let u = parse_float(parts.next(), line, "vt")?;
+                    // La coordenada v es opcional en el formato
+                    let v = match parts.next() {
+                        Some(token) => parse_float(Some(token), line, "vt")?,
+                        None => 0.0,
+                    };
+                    tex_coords.push(Vector2::new(u, v));
+                }
+                "f" => {
+                    let corners = parts
+                        .map(|token| parse_face_vertex(token, line, positions.len(), tex_coords.len(), normals.len()))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    if corners.len() < 3 {
+                        return Err(ObjError::ParseError {
+                            line,
+                            reason: format!("una cara necesita al menos 3 vértices, tiene {}", corners.len()),
+                        });
+                    }
+
+                    let mut face_indices = Vec::with_capacity(corners.len());
+                    for &corner in &corners {
+                        let (position, tex, normal) = corner;
+                        if normal.is_none() {
+                            has_normals = false;
+                        }
+                        let key = (corner, [current_color.x.to_bits(), current_color.y.to_bits(), current_color.z.to_bits()]);
+                        let index = *unique.entry(key).or_insert_with(|| {
+                            let mut vertex = Vertex::new(
+                                positions[position],
+                                normal.map(|n| normals[n]).unwrap_or(Vector3::zero()),
+                                tex.map(|t| tex_coords[t]).unwrap_or(Vector2::zero()),
+                            );
+                            vertex.color = current_color;
+                            vertices.push(vertex);
+                            (vertices.len() - 1) as u32
+                        });
+                        face_indices.push(index);
+                    }
+
+                    // Abanico desde el primer vértice: (0, i, i + 1), o (0, i + 1, i) para invertir la cara
+                    for i in 1..face_indices.len() - 1 {
+                        match winding {
+                            WindingOrder::CounterClockwise => indices.extend([face_indices[0], face_indices[i], face_indices[i + 1]]),
+                            WindingOrder::Clockwise => indices.extend([face_indices[0], face_indices[i + 1], face_indices[i]]),
+                        }
+                    }
+                }
+                "mtllib" => {
+                    for name in parts {
+                        // A missing or broken .mtl is not fatal: every face just falls back to white
+                        if let Ok((materials, _)) = tobj::load_mtl(base_dir.join(name)) {
+                            for material in &materials {
+                                if let Some([r, g, b]) = material.diffuse {
+                                    material_colors.insert(material.name.clone(), Vector3::new(r, g, b));
+                                }
+                            }
+                        }
+                    }
+                }
+                "usemtl" => {
+                    current_color = parts
+                        .next()
+                        .and_then(|name| material_colors.get(name))
+                        .copied()
+                        .unwrap_or(white);
+                }
+                // Objetos, grupos, suavizado, líneas y puntos no afectan la malla
+                _ => {}
+            }
+        }
+
+        if positions.is_empty() {
+            return Err(ObjError::MissingData("el archivo no tiene vértices (líneas v)".to_string()));
+        }
+        // Solo vértices (una nube de puntos): no hay nada que rasterizar
+        if indices.is_empty() {
+            return Err(ObjError::MissingData(format!(
+                "el archivo tiene {} vértices pero ninguna cara (líneas f)",
+                positions.len()
+            )));
+        }
+
+        let mut obj = Obj { vertices, indices, materials: material_colors };
+        if !has_normals {
+            obj.compute_normals();
+        }
+        obj.compute_tangents();
+        Ok(obj)
+    }
+
+    /// Generates a unit sphere centered at the origin with `rings` bands from pole to pole
+    /// (+Y to -Y) and `sectors` slices around Y, at least 2 and 3. The normal of each
+    /// vertex is its position. The texture coordinates go from u = 0 to 1 starting at +X
+    /// and towards +Z, and from v = 1 at the north pole to 0 at the south pole. The seam
+    /// column is duplicated so u reaches 1. The faces are counter-clockwise seen from
+    /// outside, and the pole triangles are not split into degenerate quads.
+    pub fn uv_sphere(rings: u32, sectors: u32) -> Self {
+        let rings = rings.max(2);
+        let sectors = sectors.max(3);
+
+        let mut vertices = Vec::with_capacity(((rings + 1) * (sectors + 1)) as usize);
+        for i in 0..=rings {
+            let theta = std::f32::consts::PI * i as f32 / rings as f32;
+            for j in 0..=sectors {
+                let phi = 2.0 * std::f32::consts::PI * j as f32 / sectors as f32;
+                let position = Vector3::new(theta.sin() * phi.cos(), theta.cos(), theta.sin() * phi.sin());
+                let tex_coords = Vector2::new(j as f32 / sectors as f32, 1.0 - i as f32 / rings as f32);
+                let mut vertex = Vertex::new(position, position, tex_coords);
+                vertex.color = Vector3::new(1.0, 1.0, 1.0);
+                vertices.push(vertex);
+            }
+        }
+
+        let index = |i: u32, j: u32| i * (sectors + 1) + j;
+        let mut indices = Vec::with_capacity((rings * sectors * 6) as usize);
+        for i in 0..rings {
+            for j in 0..sectors {
+                // Quad a (i, j), b (i + 1, j), c (i + 1, j + 1), d (i, j + 1); a-d-c-b turns
+                // counter-clockwise seen from outside
+                let (a, b, c, d) = (index(i, j), index(i + 1, j), index(i + 1, j + 1), index(i, j + 1));
+                if i != 0 {
+                    indices.extend([a, d, c]);
+                }
+                if i != rings - 1 {
+                    indices.extend([a, c, b]);
+                }
+            }
+        }
+
+        let mut obj = Obj { vertices, indices, materials: HashMap::new() };
+        obj.compute_tangents();
+        obj
+    }
+
+    /// Computes smooth per-vertex normals from the faces.
+    /// Each face normal comes from the cross product of two edges, so its length is
+    /// twice the triangle area and bigger faces weigh more in the average.
+    /// Vertices at the same position are averaged together, which keeps the
+    /// shading smooth across UV seams where the loader duplicated them.
+    pub fn compute_normals(&mut self) {
+        let key = |p: Vector3| (p.x.to_bits(), p.y.to_bits(), p.z.to_bits());
+        let mut accumulated: HashMap<(u32, u32, u32), Vector3> = HashMap::new();
+
+        for face in self.indices.chunks_exact(3) {
+            let a = self.vertices[face[0] as usize].position;
+            let b = self.vertices[face[1] as usize].position;
+            let c = self.vertices[face[2] as usize].position;
+
+            let e1 = Vector3::new(b.x - a.x, b.y - a.y, b.z - a.z);
+            let e2 = Vector3::new(c.x - a.x, c.y - a.y, c.z - a.z);
+            let face_normal = Vector3::new(
+                e1.y * e2.z - e1.z * e2.y,
+                e1.z * e2.x - e1.x * e2.z,
+                e1.x * e2.y - e1.y * e2.x,
+            );
+
+            // Degenerate (zero-area) triangles have no direction, skip them to avoid NaNs
+            let length = (face_normal.x * face_normal.x + face_normal.y * face_normal.y + face_normal.z * face_normal.z).sqrt();
+            if length < 1e-12 {
+                continue;
+            }
 
-        for model in models {
-            let mesh = &model.mesh;
-            let num_vertices = mesh.positions.len() / 3;
+            for position in [a, b, c] {
+                let sum = accumulated.entry(key(position)).or_insert(Vector3::zero());
+                sum.x += face_normal.x;
+                sum.y += face_normal.y;
+                sum.z += face_normal.z;
+            }
+        }
+
+        for vertex in &mut self.vertices {
+            let sum = accumulated.get(&key(vertex.position)).copied().unwrap_or(Vector3::zero());
+            let length = (sum.x * sum.x + sum.y * sum.y + sum.z * sum.z).sqrt();
+            vertex.normal = if length > 0.0 {
+                Vector3::new(sum.x / length, sum.y / length, sum.z / length)
+            } else {
+                Vector3::zero()
+            };
+            vertex.transformed_normal = vertex.normal;
+        }
+    }
+
+    /// Computes per-vertex tangents and bitangents from positions and texture coordinates.
+    /// Each face contributes the directions in which u and v grow across it; the sums are
+    /// then made orthogonal to the vertex normal (Gram-Schmidt). The bitangent is rebuilt as
+    /// `normal x tangent`, flipped when the UVs are mirrored. Vertices without usable UVs
+    /// get an arbitrary tangent perpendicular to the normal.
+    pub fn compute_tangents(&mut self) {
+        let mut tangents = vec![Vector3::zero(); self.vertices.len()];
+        let mut bitangents = vec![Vector3::zero(); self.vertices.len()];
 
-            for i in 0..num_vertices {
-                let x = mesh.positions[i * 3];
-                let y = mesh.positions[i * 3 + 1];
-                let z = mesh.positions[i * 3 + 2];
-                let position = Vector3::new(x, y, z);
+        for face in self.indices.chunks_exact(3) {
+            let [i0, i1, i2] = [face[0] as usize, face[1] as usize, face[2] as usize];
+            let (a, b, c) = (&self.vertices[i0], &self.vertices[i1], &self.vertices[i2]);
 
-                let normal = if !mesh.normals.is_empty() {
-                    let nx = mesh.normals[i * 3];
-                    let ny = mesh.normals[i * 3 + 1];
-                    let nz = mesh.normals[i * 3 + 2];
-                    Vector3::new(nx, ny, nz)
-                } else {
-                    Vector3::zero()
-                };
+            let e1 = Vector3::new(b.position.x - a.position.x, b.position.y - a.position.y, b.position.z - a.position.z);
+            let e2 = Vector3::new(c.position.x - a.position.x, c.position.y - a.position.y, c.position.z - a.position.z);
+            let (du1, dv1) = (b.tex_coords.x - a.tex_coords.x, b.tex_coords.y - a.tex_coords.y);
+            let (du2, dv2) = (c.tex_coords.x - a.tex_coords.x, c.tex_coords.y - a.tex_coords.y);
 
-                let tex_coords = if !mesh.texcoords.is_empty() {
-                    let u = mesh.texcoords[i * 2];
-                    let v = mesh.texcoords[i * 2 + 1];
-                    Vector2::new(u, v)
-                } else {
-                    Vector2::zero()
-                };
+            // Zero UV area: the face says nothing about the tangent directions
+            let det = du1 * dv2 - du2 * dv1;
+            if det.abs() < 1e-12 {
+                continue;
+            }
+            let r = 1.0 / det;
+            let tangent = Vector3::new(
+                (e1.x * dv2 - e2.x * dv1) * r,
+                (e1.y * dv2 - e2.y * dv1) * r,
+                (e1.z * dv2 - e2.z * dv1) * r,
+            );
+            let bitangent = Vector3::new(
+                (e2.x * du1 - e1.x * du2) * r,
+                (e2.y * du1 - e1.y * du2) * r,
+                (e2.z * du1 - e1.z * du2) * r,
+            );
 
-                vertices.push(Vertex::new(position, normal, tex_coords));
+            for i in [i0, i1, i2] {
+                tangents[i] += tangent;
+                bitangents[i] += bitangent;
             }
-            indices.extend_from_slice(&mesh.indices);
         }
 
-        Ok(Obj { vertices, indices })
+        for (i, vertex) in self.vertices.iter_mut().enumerate() {
+            let n = vertex.normal;
+            let t = tangents[i];
+            let n_dot_t = n.x * t.x + n.y * t.y + n.z * t.z;
+            let mut tangent = normalize(Vector3::new(t.x - n.x * n_dot_t, t.y - n.y * n_dot_t, t.z - n.z * n_dot_t));
+            if tangent == Vector3::zero() {
+                let axis = if n.x.abs() < 0.9 { Vector3::new(1.0, 0.0, 0.0) } else { Vector3::new(0.0, 1.0, 0.0) };
+                tangent = normalize(cross(&cross(&n, &axis), &n));
+            }
+
+            let mut bitangent = cross(&n, &tangent);
+            let b = bitangents[i];
+            if bitangent.x * b.x + bitangent.y * b.y + bitangent.z * b.z < 0.0 {
+                bitangent *= -1.0;
+            }
+
+            vertex.tangent = tangent;
+            vertex.bitangent = bitangent;
+            vertex.transformed_tangent = tangent;
+            vertex.transformed_bitangent = bitangent;
+        }
+    }
+
+    /// Vertex and triangle counts plus the bounding box, found in one pass over the
+    /// positions. A mesh without vertices gets an empty box at the origin.
+    pub fn stats(&self) -> MeshStats {
+        let mut positions = self.vertices.iter().map(|vertex| vertex.position);
+        let (min, max) = match positions.next() {
+            Some(first) => positions.fold((first, first), |(min, max), p| {
+                (
+                    Vector3::new(min.x.min(p.x), min.y.min(p.y), min.z.min(p.z)),
+                    Vector3::new(max.x.max(p.x), max.y.max(p.y), max.z.max(p.z)),
+                )
+            }),
+            None => (Vector3::zero(), Vector3::zero()),
+        };
+        MeshStats {
+            vertex_count: self.vertices.len(),
+            triangle_count: self.indices.len() / 3,
+            min,
+            max,
+        }
+    }
+
+    /// Diffuse (`Kd`) colors parsed from the `.mtl` file, keyed by material name
+    pub fn materials(&self) -> &HashMap<String, Vector3> {
+        &self.materials
     }
 
+    /// One vertex per triangle corner, three per triangle, ready for `render_mesh`.
+    /// Empty when the mesh has no faces, which `load` and `parse` already reject;
+    /// it can only happen with an `Obj` whose `indices` were cleared by hand.
     pub fn get_vertex_array(&self) -> Vec<Vertex> {
         let mut vertex_array = Vec::new();
         for &index in &self.indices {
@@ -58,4 +432,174 @@ impl Obj {
         vertex_array
     }
 }
-    
\ No newline at end of file
+    
+
+fn cross(a: &Vector3, b: &Vector3) -> Vector3 {
+    Vector3::new(
+        a.y * b.z - a.z * b.y,
+        a.z * b.x - a.x * b.z,
+        a.x * b.y - a.y * b.x,
+    )
+}
+
+// Unit vector in the same direction, or zero for a zero-length input
+fn normalize(v: Vector3) -> Vector3 {
+    let length = (v.x * v.x + v.y * v.y + v.z * v.z).sqrt();
+    if length > 1e-12 {
+        Vector3::new(v.x / length, v.y / length, v.z / length)
+    } else {
+        Vector3::zero()
+    }
+}
+
+fn parse_float(token: Option<&str>, line: usize, keyword: &str) -> Result<f32, ObjError> {
+    let token = token.ok_or_else(|| ObjError::ParseError {
+        line,
+        reason: format!("faltan coordenadas en '{}'", keyword),
+    })?;
+    token.parse::<f32>().map_err(|_| ObjError::ParseError {
+        line,
+        reason: format!("'{}' no es un número válido en '{}'", token, keyword),
+    })
+}
+
+// Extra values after the third (w, vertex colors) are ignored
+fn parse_vector3<'a>(parts: &mut impl Iterator<Item = &'a str>, line: usize, keyword: &str) -> Result<Vector3, ObjError> {
+    let x = parse_float(parts.next(), line, keyword)?;
+    let y = parse_float(parts.next(), line, keyword)?;
+    let z = parse_float(parts.next(), line, keyword)?;
+    Ok(Vector3::new(x, y, z))
+}
+
+// Resolves a 1-based OBJ index (negative counts back from the last element parsed so far)
+// to a 0-based index into a list of `count` elements
+fn resolve_index(token: &str, count: usize, line: usize, what: &str) -> Result<usize, ObjError> {
+    let index: i64 = token.parse().map_err(|_| ObjError::ParseError {
+        line,
+        reason: format!("índice de {} inválido: '{}'", what, token),
+    })?;
+    let resolved = if index > 0 { index - 1 } else { count as i64 + index };
+    if index == 0 || resolved < 0 || resolved >= count as i64 {
+        return Err(ObjError::ParseError {
+            line,
+            reason: format!("índice de {} fuera de rango: {} (hay {})", what, index, count),
+        });
+    }
+    Ok(resolved as usize)
+}
+
+// Position, texture coordinate and normal indices (0-based) of one face corner
+type FaceVertex = (usize, Option<usize>, Option<usize>);
+
+// One corner of a face in any of the forms `v`, `v/vt`, `v//vn` or `v/vt/vn`
+fn parse_face_vertex(
+    token: &str,
+    line: usize,
+    position_count: usize,
+    tex_count: usize,
+    normal_count: usize,
+) -> Result<FaceVertex, ObjError> {
+    let mut fields = token.split('/');
+    let position = resolve_index(fields.next().unwrap_or(""), position_count, line, "vértice")?;
+    let tex = match fields.next() {
+        Some("") | None => None,
+        Some(field) => Some(resolve_index(field, tex_count, line, "textura")?),
+    };
+    let normal = match fields.next() {
+        Some("") | None => None,
+        Some(field) => Some(resolve_index(field, normal_count, line, "normal")?),
+    };
+    Ok((position, tex, normal))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(source: &str) -> Result<Obj, ObjError> {
+        Obj::parse(source, Path::new(""))
+    }
+
+    #[test]
+    fn truncated_face_is_a_parse_error_with_its_line() {
+        let source = "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2\n";
+        match parse(source) {
+            Err(ObjError::ParseError { line, .. }) => assert_eq!(line, 4),
+            other => panic!("expected a parse error, got {:?}", other.map(|obj| obj.indices)),
+        }
+    }
+
+    #[test]
+    fn missing_file_is_an_io_error() {
+        match Obj::load("models/does_not_exist.obj") {
+            Err(ObjError::IoError(_)) => {}
+            other => panic!("expected an io error, got {:?}", other.map(|obj| obj.indices)),
+        }
+    }
+
+    // Normal of the triangle a, b, c by the right-hand rule (counter-clockwise faces +Z here)
+    fn face_normal(triangle: &[Vertex]) -> Vector3 {
+        let (a, b, c) = (triangle[0].position, triangle[1].position, triangle[2].position);
+        cross(&(b - a), &(c - a))
+    }
+
+    #[test]
+    fn quad_becomes_two_triangles_with_the_same_winding() {
+        let source = "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nf 1 2 3 4\n";
+        let vertices = parse(source).unwrap().get_vertex_array();
+        assert_eq!(vertices.len(), 6);
+
+        let positions: Vec<(f32, f32)> = vertices.iter().map(|v| (v.position.x, v.position.y)).collect();
+        assert_eq!(positions, [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 0.0), (1.0, 1.0), (0.0, 1.0)]);
+        for triangle in vertices.chunks(3) {
+            assert!(face_normal(triangle).z > 0.0);
+        }
+    }
+
+    #[test]
+    fn negative_indices_match_absolute_ones() {
+        let vertices = "v 0 0 0\nv 1 0 0\nv 0 1 0\nvt 0 0\nvt 1 0\nvt 0 1\nvn 0 0 1\n";
+        let relative = parse(&format!("{}f -3/-3/-1 -2/-2/-1 -1/-1/-1\n", vertices)).unwrap().get_vertex_array();
+        let absolute = parse(&format!("{}f 1/1/1 2/2/1 3/3/1\n", vertices)).unwrap().get_vertex_array();
+        assert_eq!(relative.len(), 3);
+        for (r, a) in relative.iter().zip(&absolute) {
+            assert_eq!(r.position, a.position);
+            assert_eq!(r.tex_coords, a.tex_coords);
+            assert_eq!(r.normal, a.normal);
+        }
+
+        let relative = parse(&format!("{}f -3 -2 -1\n", vertices)).unwrap().get_vertex_array();
+        let absolute = parse(&format!("{}f 1 2 3\n", vertices)).unwrap().get_vertex_array();
+        let positions = |array: &[Vertex]| array.iter().map(|v| v.position).collect::<Vec<_>>();
+        assert_eq!(positions(&relative), positions(&absolute));
+    }
+
+    // Writes `source` to a file in the temp dir and returns its path
+    fn temp_obj(name: &str, source: &str) -> String {
+        let path = std::env::temp_dir().join(name);
+        std::fs::write(&path, source).unwrap();
+        path.to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn flipping_the_winding_reverses_the_face_normal() {
+        let path = temp_obj("ship_winding.obj", "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n");
+        let ccw = Obj::load_with_options(&path, WindingOrder::CounterClockwise).unwrap().get_vertex_array();
+        let cw = Obj::load_with_options(&path, WindingOrder::Clockwise).unwrap().get_vertex_array();
+
+        assert!(face_normal(&ccw).z > 0.0);
+        assert!(face_normal(&cw).z < 0.0);
+        // The computed vertex normals follow the face
+        assert!(ccw.iter().all(|v| v.normal.z > 0.0));
+        assert!(cw.iter().all(|v| v.normal.z < 0.0));
+    }
+
+    #[test]
+    fn vertices_only_file_is_missing_data() {
+        let path = temp_obj("ship_points.obj", "v 0 0 0\nv 1 0 0\nv 0 1 0\n");
+        match Obj::load(&path) {
+            Err(ObjError::MissingData(_)) => {}
+            other => panic!("expected missing data, got {:?}", other.map(|obj| obj.indices)),
+        }
+    }
+}
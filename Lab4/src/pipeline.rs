@@ -0,0 +1,83 @@
+// pipeline.rs
+use raylib::prelude::*;
+use crate::vertex::Vertex;
+use crate::fragment::Fragment;
+use crate::framebuffer::Framebuffer;
+use crate::light::Light;
+use crate::triangle::triangle;
+use crate::shaders::vertex_shader;
+use crate::clipping::{clip_triangle, is_offscreen, is_backface};
+use crate::{Uniforms, RenderScratch};
+
+/// How a shaded fragment is written to the framebuffer
+pub enum FragmentOutput {
+    /// Depth-tested write of color and depth
+    Opaque(Vector3),
+    /// Depth-tested blend over what is already there, with the given alpha; depth is not written
+    Blend(Vector3, f32),
+}
+
+/// Draws a triangle list (three vertices per triangle) through the whole pipeline:
+/// vertex shader with `uniforms` (its `render_type` picks the transform), near-plane
+/// clipping, off-screen and (with `uniforms.cull_backfaces`) back-face culling, then
+/// rasterization. The rings (render_type 1) are seen from both sides and never
+/// back-face culled. `shade` is called for every fragment and decides its output;
+/// returning None discards the fragment. With `uniforms.debug_view` on, the debug color
+/// replaces the shaded one but the output kind (and discards) are kept, so the rings'
+/// gaps and the clouds' transparency stay visible. Culled triangles are added to
+/// `scratch.culled_triangles`.
+pub fn render_mesh<F>(
+    framebuffer: &mut Framebuffer,
+    scratch: &mut RenderScratch,
+    uniforms: &Uniforms,
+    vertices: &[Vertex],
+    lights: &[Light],
+    mut shade: F,
+) where
+    F: FnMut(&Fragment) -> Option<FragmentOutput>,
+{
+    scratch.clear();
+    for vertex in vertices {
+        let transformed = vertex_shader(vertex, uniforms);
+        scratch.transformed_vertices.push(transformed);
+    }
+
+    let cull_backfaces = uniforms.cull_backfaces && uniforms.render_type != 1;
+    let transformed_vertices = &scratch.transformed_vertices;
+    for i in (0..transformed_vertices.len()).step_by(3) {
+        if i + 2 < transformed_vertices.len() {
+            let tri = [
+                transformed_vertices[i].clone(),
+                transformed_vertices[i + 1].clone(),
+                transformed_vertices[i + 2].clone(),
+            ];
+            for clipped in clip_triangle(&tri, &uniforms.viewport_matrix) {
+                if is_offscreen(&clipped, framebuffer.width, framebuffer.height)
+                    || (cull_backfaces && is_backface(&clipped))
+                {
+                    scratch.culled_triangles += 1;
+                } else {
+                    scratch.triangles.push(clipped);
+                }
+            }
+        }
+    }
+
+    // Each fragment is shaded and written as soon as it is rasterized, no intermediate Vec
+    for tri in &scratch.triangles {
+        triangle(&tri[0], &tri[1], &tri[2], lights, uniforms.shading_mode, |fragment| {
+            let x = fragment.position.x as i32;
+            let y = fragment.position.y as i32;
+            let output = match (shade(&fragment), uniforms.debug_view.color(&fragment)) {
+                (Some(FragmentOutput::Opaque(_)), Some(debug)) => Some(FragmentOutput::Opaque(debug)),
+                (Some(FragmentOutput::Blend(_, alpha)), Some(debug)) => Some(FragmentOutput::Blend(debug, alpha)),
+                (output, _) => output,
+            };
+            match output {
+                Some(FragmentOutput::Opaque(color)) => framebuffer.point(x, y, color, fragment.depth),
+                Some(FragmentOutput::Blend(color, alpha)) => framebuffer.blend_point(x, y, color, alpha, fragment.depth),
+                None => {}
+            }
+        });
+    }
+}
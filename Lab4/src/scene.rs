@@ -0,0 +1,67 @@
+// scene.rs
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Tuned settings kept between runs: active planet, camera view and per-planet
+/// spin and tilt. Stored as JSON; vectors are plain arrays so the file is easy to edit.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Scene {
+    pub planet_type: i32,
+    pub camera_eye: [f32; 3],
+    pub camera_target: [f32; 3],
+    pub camera_up: [f32; 3],
+    pub rotation_speeds: Vec<f32>, // one per planet type, rad/s
+    pub axial_tilts: Vec<f32>,     // one per planet type, degrees
+    pub time_scale: f32,
+}
+
+#[derive(Debug)]
+pub enum SceneError {
+    Io(std::io::Error),
+    Format(serde_json::Error),
+    Invalid(String),
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SceneError::Io(e) => write!(f, "error de archivo: {}", e),
+            SceneError::Format(e) => write!(f, "JSON inválido: {}", e),
+            SceneError::Invalid(reason) => write!(f, "escena inválida: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for SceneError {}
+
+impl Scene {
+    pub fn save(&self, path: &str) -> Result<(), SceneError> {
+        let json = serde_json::to_string_pretty(self).map_err(SceneError::Format)?;
+        std::fs::write(path, json).map_err(SceneError::Io)
+    }
+
+    /// Reads a scene and checks that it can be applied as is: a known planet type
+    /// and one finite speed and tilt for each of the `planet_count` planets.
+    /// Their range is left to the caller, which clamps them to its own limits
+    pub fn load(path: &str, planet_count: usize) -> Result<Self, SceneError> {
+        let json = std::fs::read_to_string(path).map_err(SceneError::Io)?;
+        let scene: Scene = serde_json::from_str(&json).map_err(SceneError::Format)?;
+
+        if scene.planet_type < 0 || scene.planet_type as usize >= planet_count {
+            return Err(SceneError::Invalid(format!("planeta {} no existe", scene.planet_type)));
+        }
+        if scene.rotation_speeds.len() != planet_count || scene.axial_tilts.len() != planet_count {
+            return Err(SceneError::Invalid(format!(
+                "se esperaban {} velocidades e inclinaciones",
+                planet_count
+            )));
+        }
+        if scene.rotation_speeds.iter().chain(&scene.axial_tilts).any(|value| !value.is_finite()) {
+            return Err(SceneError::Invalid("velocidades e inclinaciones deben ser números finitos".to_string()));
+        }
+        if !scene.time_scale.is_finite() || scene.time_scale < 0.0 {
+            return Err(SceneError::Invalid(format!("escala de tiempo {} inválida", scene.time_scale)));
+        }
+        Ok(scene)
+    }
+}
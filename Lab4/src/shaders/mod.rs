@@ -0,0 +1,896 @@
+// shaders/mod.rs
+mod noise;
+mod planets;
+
+use raylib::prelude::*;
+use crate::vertex::Vertex;
+use crate::{Uniforms, ShadingMode, LightingModel, RenderScratch};
+use crate::matrix::{multiply_matrix_vector4, invert, create_model_matrix};
+use crate::fragment::Fragment;
+use crate::framebuffer::Framebuffer;
+use crate::pipeline::{render_mesh, FragmentOutput};
+use crate::light::{Light, ambient_light};
+use crate::clipping::clip_to_screen;
+use noise::fractal_noise;
+use planets::{
+    rocky_planet_color, gaseous_planet_color, biolum_planet_color, ringed_planet_color,
+    ice_planet_color, lava_planet_color, rotate_planet_position, sample_normal_map,
+    perturb_normal, NORMAL_MAP_STRENGTH,
+};
+// Parte de la API del módulo aunque main no lo use directamente
+#[allow(unused_imports)]
+pub use noise::gradient_noise;
+pub use planets::{planet_rotation_speed, planet_axial_tilt, planet_shininess, planet_atmosphere, planet_lighting_model, generate_craters, generate_craters_with_sizes, CraterSizes};
+
+// Trait para interpolación lineal
+pub trait Lerp {
+    fn lerp(self, other: Self, t: f32) -> Self;
+}
+
+impl Lerp for Vector3 {
+    fn lerp(self, other: Self, t: f32) -> Self {
+        let t = t.max(0.0).min(1.0);
+        Vector3::new(
+            self.x + (other.x - self.x) * t,
+            self.y + (other.y - self.y) * t,
+            self.z + (other.z - self.z) * t,
+        )
+    }
+}
+
+/// Parámetros de la órbita de una luna
+#[derive(Clone, Copy, Debug)]
+pub struct MoonParams {
+    pub orbit_radius: f32,
+    pub speed: f32,       // radianes por segundo
+    pub inclination: f32, // inclinación del plano orbital (radianes, alrededor de X)
+    pub scale: f32,       // radio de la luna relativo al planeta
+    pub phase: f32,       // desfase inicial en la órbita (radianes)
+    pub wobble: f32,      // altura del vaivén sobre el plano de la órbita (tres por vuelta)
+}
+
+impl Default for MoonParams {
+    fn default() -> Self {
+        MoonParams {
+            orbit_radius: 2.8,
+            speed: 0.4,
+            inclination: 0.0,
+            scale: 0.25,
+            phase: 0.0,
+            wobble: 0.2,
+        }
+    }
+}
+
+impl MoonParams {
+    /// Centro de la luna en el tiempo dado
+    pub fn position(&self, time: f32) -> Vector3 {
+        let moon_orbit_time = time * self.speed + self.phase;
+        let moon_x = self.orbit_radius * moon_orbit_time.cos();
+        let moon_z = self.orbit_radius * moon_orbit_time.sin();
+        let moon_y = (moon_orbit_time * 3.0).sin() * self.wobble;
+
+        // Inclinar el plano de la órbita
+        let (sin_i, cos_i) = self.inclination.sin_cos();
+        Vector3::new(
+            moon_x,
+            moon_y * cos_i - moon_z * sin_i,
+            moon_y * sin_i + moon_z * cos_i,
+        )
+    }
+}
+
+// Radios para los que están pensadas las bandas de RING_BANDS (los de RingParams::default())
+const RING_INNER: f32 = 1.6;
+const RING_OUTER: f32 = 2.4;
+
+/// Forma de los anillos del planeta 3. Las bandas se estiran o encogen para ocupar
+/// `inner..outer`, así que conservan sus proporciones y huecos
+#[derive(Clone, Copy, Debug)]
+pub struct RingParams {
+    pub inner: f32,     // radio interior, en radios del planeta
+    pub outer: f32,     // radio exterior (mayor que inner)
+    pub tilt: f32,      // inclinación respecto al ecuador del planeta (radianes, alrededor de X)
+    pub thickness: f32, // grosor del disco, como fracción del radio del planeta
+    pub shadow_softness: f32, // radio angular de la luz (radianes) para la penumbra de su sombra sobre el planeta
+}
+
+impl Default for RingParams {
+    fn default() -> Self {
+        RingParams {
+            inner: RING_INNER,
+            outer: RING_OUTER,
+            tilt: 0.0,
+            thickness: 0.05,
+            shadow_softness: 0.04,
+        }
+    }
+}
+
+impl RingParams {
+    // Radio de los anillos originales (RING_INNER..RING_OUTER) -> radio de estos anillos
+    fn scale_radius(&self, radius: f32) -> f32 {
+        self.inner + (radius - RING_INNER) / (RING_OUTER - RING_INNER) * (self.outer - self.inner)
+    }
+
+    // Inverso de scale_radius: en qué radio de los anillos originales cae `radius`
+    fn band_radius(&self, radius: f32) -> f32 {
+        RING_INNER + (radius - self.inner) / (self.outer - self.inner).max(1e-6) * (RING_OUTER - RING_INNER)
+    }
+
+    // Del plano del ecuador al plano inclinado de los anillos
+    fn tilted(&self, p: Vector3) -> Vector3 {
+        let (sin_t, cos_t) = self.tilt.sin_cos();
+        Vector3::new(p.x, p.y * cos_t - p.z * sin_t, p.y * sin_t + p.z * cos_t)
+    }
+
+    // Inverso de tilted: del plano de los anillos al del ecuador
+    fn untilted(&self, p: Vector3) -> Vector3 {
+        let (sin_t, cos_t) = self.tilt.sin_cos();
+        Vector3::new(p.x, p.y * cos_t + p.z * sin_t, -p.y * sin_t + p.z * cos_t)
+    }
+}
+
+// Radio de la capa de nubes relativo a la superficie del planeta
+const CLOUD_SHELL_SCALE: f32 = 1.03;
+
+pub fn vertex_shader(vertex: &Vertex, uniforms: &Uniforms) -> Vertex {
+    let mut position_vec4 = Vector4::new(
+        vertex.position.x,
+        vertex.position.y,
+        vertex.position.z,
+        1.0
+    );
+
+    match uniforms.render_type {
+        1 => { // rings
+            // La esfera se aplasta en un disco plano en XZ: la longitud da el ángulo y la
+            // latitud el radio. Cada hemisferio va del polo (RING_INNER) al ecuador
+            // (RING_OUTER) con paso parejo en latitud, y queda como una cara del disco: la
+            // de arriba a +thickness/2 y la de abajo a -thickness/2, unidas en el borde exterior.
+            // Se mide con la normal y no con la posición porque sphere.obj no está centrada
+            // en el origen ni es del todo redonda, y el borde saldría ondulado
+            let rings = &uniforms.rings;
+            let n = vertex.normal;
+            let angle = (n.z.atan2(n.x) + uniforms.time * 0.2) % (2.0 * std::f32::consts::PI);
+            let length = (n.x * n.x + n.y * n.y + n.z * n.z).sqrt().max(1e-6);
+            let height = (n.y / length).clamp(-1.0, 1.0);
+            let latitude = height.abs().acos() / (std::f32::consts::PI / 2.0); // 0 en el polo, 1 en el ecuador
+            let base_radius = rings.scale_radius(RING_INNER + latitude * (RING_OUTER - RING_INNER));
+            // El último tramo antes del ecuador redondea el borde en vez de cortarlo en seco
+            let face = (height * 8.0).clamp(-1.0, 1.0);
+            let ring_position = rings.tilted(Vector3::new(
+                base_radius * angle.cos(),
+                face * rings.thickness * 0.5,
+                base_radius * angle.sin(),
+            ));
+            position_vec4.x = ring_position.x;
+            position_vec4.y = ring_position.y;
+            position_vec4.z = ring_position.z;
+        }
+        2 => { // moon
+            let moon = &uniforms.moon;
+            let moon_base = moon.position(uniforms.time);
+            position_vec4.x = moon_base.x + vertex.position.x * moon.scale;
+            position_vec4.y = moon_base.y + vertex.position.y * moon.scale;
+            position_vec4.z = moon_base.z + vertex.position.z * moon.scale;
+        }
+        3 => { // clouds
+            position_vec4.x *= CLOUD_SHELL_SCALE;
+            position_vec4.y *= CLOUD_SHELL_SCALE;
+            position_vec4.z *= CLOUD_SHELL_SCALE;
+        }
+        4 => {} // sol: lo ubica y escala la matriz de modelo
+        _ => {}
+    }
+
+    let world_position = multiply_matrix_vector4(&uniforms.model_matrix, &position_vec4);
+    let view_position = multiply_matrix_vector4(&uniforms.view_matrix, &world_position);
+    let clip_position = multiply_matrix_vector4(&uniforms.projection_matrix, &view_position);
+
+    let transformed_position = clip_to_screen(&clip_position, &uniforms.viewport_matrix);
+
+    // Los anillos se sombrean según su radio y las lunas según su lugar en la órbita,
+    // así que conservan la posición ya desplazada
+    let position = if uniforms.render_type == 1 || uniforms.render_type == 2 {
+        Vector3::new(position_vec4.x, position_vec4.y, position_vec4.z)
+    } else {
+        vertex.position
+    };
+
+    // La normal y la base tangente quedan en coordenadas del objeto, igual que `position`:
+    // ahí se sombrea todo (anillos, lunas, sombras y las luces de object_space_lights)
+    Vertex {
+        position,
+        normal: vertex.normal,
+        tex_coords: vertex.tex_coords,
+        color: vertex.color,
+        transformed_position,
+        transformed_normal: unit(&vertex.normal),
+        tangent: vertex.tangent,
+        bitangent: vertex.bitangent,
+        transformed_tangent: unit(&vertex.tangent),
+        transformed_bitangent: unit(&vertex.bitangent),
+        clip_position,
+    }
+}
+
+// Vector normalizado; el vector cero (mallas sin tangentes) se queda en cero
+fn unit(direction: &Vector3) -> Vector3 {
+    let mut n = *direction;
+    let len = (n.x * n.x + n.y * n.y + n.z * n.z).sqrt();
+    if len > 0.0 { n.x /= len; n.y /= len; n.z /= len; }
+    n
+}
+
+/// Luces del mundo llevadas a las coordenadas del objeto de `model_matrix`. Los shaders
+/// sombrean en esas coordenadas (las del planeta, donde están el ruido, los anillos y las
+/// lunas), así que cada pase convierte las luces antes de iluminar y la inclinación o la
+/// órbita del cuerpo cambian qué lado mira a la luz. La atenuación se ajusta a la escala
+/// del modelo para seguir dependiendo de la distancia en el mundo.
+/// Si el modelo no es invertible las luces se devuelven tal cual.
+pub fn object_space_lights(lights: &[Light], model_matrix: &Matrix) -> Vec<Light> {
+    let inverse = match invert(model_matrix) {
+        Some(inverse) => inverse,
+        None => return lights.to_vec(),
+    };
+    let m = model_matrix;
+    let scale = (m.m0 * m.m0 + m.m1 * m.m1 + m.m2 * m.m2).sqrt();
+    lights.iter().map(|light| {
+        let mut light = light.clone();
+        light.position = transform_point(&light.position, &inverse);
+        light.linear *= scale;
+        light.quadratic *= scale * scale;
+        light
+    }).collect()
+}
+
+/// Punto del mundo (la cámara, por ejemplo) en las coordenadas del objeto de
+/// `model_matrix`, donde trabajan los shaders. Si el modelo no es invertible queda igual.
+pub fn object_space_point(point: &Vector3, model_matrix: &Matrix) -> Vector3 {
+    match invert(model_matrix) {
+        Some(inverse) => transform_point(point, &inverse),
+        None => *point,
+    }
+}
+
+fn transform_point(point: &Vector3, matrix: &Matrix) -> Vector3 {
+    let p = multiply_matrix_vector4(matrix, &Vector4::new(point.x, point.y, point.z, 1.0));
+    Vector3::new(p.x, p.y, p.z)
+}
+
+// Suma difusa (Lambert) de todas las luces, sin piso de ambiente
+fn diffuse_lighting(normal: &Vector3, point: &Vector3, lights: &[Light]) -> f32 {
+    let mut total = 0.0;
+    for light in lights {
+        let light_dir = light.direction_to(point);
+        let dot = normal.x * light_dir.x + normal.y * light_dir.y + normal.z * light_dir.z;
+        total += dot.max(0.0) * light.intensity * light.attenuation(point);
+    }
+    total
+}
+
+// Suma difusa con el color de cada luz: una luz blanca da lo mismo que diffuse_lighting en los tres canales
+fn diffuse_color(normal: &Vector3, point: &Vector3, lights: &[Light]) -> Vector3 {
+    let mut total = Vector3::new(0.0, 0.0, 0.0);
+    for light in lights {
+        let light_dir = light.direction_to(point);
+        let dot = normal.x * light_dir.x + normal.y * light_dir.y + normal.z * light_dir.z;
+        total += light.color * (dot.max(0.0) * light.intensity * light.attenuation(point));
+    }
+    total
+}
+
+// Recorta cada canal de una iluminación de color por separado
+fn clamp_channels(light: Vector3, min: f32, max: f32) -> Vector3 {
+    Vector3::new(light.x.clamp(min, max), light.y.clamp(min, max), light.z.clamp(min, max))
+}
+
+fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+fn simulate_lighting(normal: &Vector3, point: &Vector3, lights: &[Light]) -> Vector3 {
+    clamp_channels(diffuse_color(normal, point, lights), ambient_light(lights), 1.0) // mínimo ambiente
+}
+
+// Ancho del terminador suave, en coseno del ángulo con la luz (también marca dónde empieza la noche)
+const TERMINATOR_WIDTH: f32 = 0.3;
+// Cuánto pasa la luz envolvente más allá del terminador (0 = Lambert)
+const WRAP_AMOUNT: f32 = 0.5;
+// Blanqueo máximo del borde en el modelo Fresnel
+const FRESNEL_STRENGTH: f32 = 0.3;
+
+// Qué tan de noche está un punto: 0 con luz de lleno, 1 pasado el terminador suave.
+// Lo usan los planetas que emiten luz propia del lado oscuro
+fn night_side(normal: &Vector3, point: &Vector3, lights: &[Light]) -> f32 {
+    1.0 - smoothstep(0.0, TERMINATOR_WIDTH, diffuse_lighting(normal, point, lights))
+}
+
+// Ilumina el color base (`albedo`) de la superficie con el modelo de iluminación elegido
+fn shade(model: LightingModel, albedo: Vector3, normal: &Vector3, point: &Vector3, lights: &[Light]) -> Vector3 {
+    let ambient = ambient_light(lights);
+    match model {
+        LightingModel::Lambert => albedo * simulate_lighting(normal, point, lights),
+        LightingModel::Soft => {
+            // Cada canal va del ambiente a su valor con luz a lo largo de la banda
+            let day = 1.0 - night_side(normal, point, lights);
+            let tint = diffuse_color(normal, point, lights);
+            let channel = |c: f32| ambient + (c.max(ambient) - ambient) * day;
+            albedo * Vector3::new(channel(tint.x), channel(tint.y), channel(tint.z))
+        }
+        LightingModel::Wrap => {
+            let mut total = Vector3::new(0.0, 0.0, 0.0);
+            for light in lights {
+                let light_dir = light.direction_to(point);
+                let dot = normal.x * light_dir.x + normal.y * light_dir.y + normal.z * light_dir.z;
+                let wrapped = ((dot + WRAP_AMOUNT) / (1.0 + WRAP_AMOUNT)).max(0.0);
+                total += light.color * (wrapped * light.intensity * light.attenuation(point));
+            }
+            albedo * clamp_channels(total, ambient, 1.0)
+        }
+        LightingModel::Fresnel => {
+            // El borde respecto a la luz se aclara como un reflejo; sin tope arriba
+            let dot = diffuse_lighting(normal, point, lights);
+            let fresnel = (1.0 - dot.abs()).powi(3);
+            let albedo = albedo.lerp(Vector3::new(1.0, 1.0, 1.0), fresnel * FRESNEL_STRENGTH);
+            albedo * clamp_channels(diffuse_color(normal, point, lights), ambient, f32::MAX)
+        }
+    }
+}
+
+// Especular Blinn-Phong: usa el vector medio entre la luz y la vista
+fn specular_lighting(normal: &Vector3, point: &Vector3, camera_position: &Vector3, lights: &[Light], shininess: f32) -> Vector3 {
+    let mut view_dir = Vector3::new(
+        camera_position.x - point.x,
+        camera_position.y - point.y,
+        camera_position.z - point.z,
+    );
+    let view_len = (view_dir.x * view_dir.x + view_dir.y * view_dir.y + view_dir.z * view_dir.z).sqrt();
+    if view_len > 0.0 { view_dir.x /= view_len; view_dir.y /= view_len; view_dir.z /= view_len; }
+
+    let mut total = Vector3::new(0.0, 0.0, 0.0);
+    for light in lights {
+        let light_dir = light.direction_to(point);
+        let mut half = Vector3::new(light_dir.x + view_dir.x, light_dir.y + view_dir.y, light_dir.z + view_dir.z);
+        let half_len = (half.x * half.x + half.y * half.y + half.z * half.z).sqrt();
+        if half_len > 0.0 { half.x /= half_len; half.y /= half_len; half.z /= half_len; }
+
+        // Sin brillo en el lado oscuro
+        let n_dot_l = normal.x * light_dir.x + normal.y * light_dir.y + normal.z * light_dir.z;
+        if n_dot_l <= 0.0 {
+            continue;
+        }
+        let n_dot_h = (normal.x * half.x + normal.y * half.y + normal.z * half.z).max(0.0);
+        total += light.color * (n_dot_h.powf(shininess) * light.intensity * light.attenuation(point));
+    }
+    total
+}
+
+// Brillo de borde: máximo donde la superficie se ve de canto
+fn rim_term(normal: &Vector3, point: &Vector3, camera_position: &Vector3, power: f32) -> f32 {
+    let mut view_dir = Vector3::new(
+        camera_position.x - point.x,
+        camera_position.y - point.y,
+        camera_position.z - point.z,
+    );
+    let view_len = (view_dir.x * view_dir.x + view_dir.y * view_dir.y + view_dir.z * view_dir.z).sqrt();
+    if view_len > 0.0 { view_dir.x /= view_len; view_dir.y /= view_len; view_dir.z /= view_len; }
+
+    let n_dot_v = (normal.x * view_dir.x + normal.y * view_dir.y + normal.z * view_dir.z).max(0.0);
+    (1.0 - n_dot_v).powf(power)
+}
+
+// Render rings with procedural texture
+/// Banda concéntrica de los anillos, en radios del planeta
+struct RingBand {
+    inner: f32,
+    outer: f32,
+    opacity: f32,    // densidad de la banda (0 = vacía, 1 = opaca)
+    brightness: f32, // multiplicador del color base
+}
+
+// Los huecos entre bandas (división de Cassini, división de Encke) no se dibujan
+const RING_BANDS: [RingBand; 5] = [
+    RingBand { inner: 1.60, outer: 1.75, opacity: 0.35, brightness: 0.70 }, // anillo C, tenue
+    RingBand { inner: 1.75, outer: 2.05, opacity: 1.00, brightness: 1.00 }, // anillo B, el más denso
+    RingBand { inner: 2.12, outer: 2.27, opacity: 0.80, brightness: 0.90 }, // anillo A interior
+    RingBand { inner: 2.29, outer: 2.35, opacity: 0.75, brightness: 0.85 }, // anillo A exterior
+    RingBand { inner: 2.38, outer: 2.40, opacity: 0.50, brightness: 0.95 }, // anillo F, muy delgado
+];
+
+/// Fracción de luz que llega a `point` desde `light` sin que la bloquee el planeta
+/// (esfera de radio 1 en el origen). Se mide qué tan cerca del centro pasa el rayo
+/// hacia la luz; `softness` es el ancho del borde de la sombra en radios del planeta.
+fn planet_shadow(point: &Vector3, light: &Light, softness: f32) -> f32 {
+    let dir = light.direction_to(point);
+    let along = point.x * dir.x + point.y * dir.y + point.z * dir.z;
+    // El rayo se aleja del planeta: nada lo bloquea
+    if along >= 0.0 {
+        return 1.0;
+    }
+    let closest = Vector3::new(
+        point.x - dir.x * along,
+        point.y - dir.y * along,
+        point.z - dir.z * along,
+    );
+    let distance = (closest.x * closest.x + closest.y * closest.y + closest.z * closest.z).sqrt();
+    if softness <= 0.0 {
+        return if distance < 1.0 { 0.0 } else { 1.0 };
+    }
+    smoothstep(1.0 - softness, 1.0 + softness, distance)
+}
+
+/// Fracción de luz que llega a `point` desde `light` sin que la tape ninguna luna.
+/// Igual que planet_shadow pero con la esfera de cada luna en su lugar de la órbita;
+/// `softness` es el ancho de la penumbra en radios de la luna.
+fn moon_shadow(point: &Vector3, light: &Light, moons: &[MoonParams], time: f32, softness: f32) -> f32 {
+    let dir = light.direction_to(point);
+    let mut visible = 1.0;
+    for moon in moons {
+        let center = moon.position(time);
+        let to_moon = Vector3::new(center.x - point.x, center.y - point.y, center.z - point.z);
+        let along = to_moon.x * dir.x + to_moon.y * dir.y + to_moon.z * dir.z;
+        // La luna está detrás del punto respecto a la luz
+        if along <= 0.0 {
+            continue;
+        }
+        let closest = Vector3::new(
+            to_moon.x - dir.x * along,
+            to_moon.y - dir.y * along,
+            to_moon.z - dir.z * along,
+        );
+        let distance = (closest.x * closest.x + closest.y * closest.y + closest.z * closest.z).sqrt();
+        visible *= if softness <= 0.0 {
+            if distance < moon.scale { 0.0 } else { 1.0 }
+        } else {
+            smoothstep(moon.scale * (1.0 - softness), moon.scale * (1.0 + softness), distance)
+        };
+    }
+    visible
+}
+
+// Luces con la intensidad reducida por los eclipses en `point`.
+// None si ninguna luna tapa ninguna luz, que es el caso normal y no reserva memoria.
+fn eclipsed_lights(point: &Vector3, lights: &[Light], uniforms: &Uniforms) -> Option<Vec<Light>> {
+    if uniforms.shadow_moons.is_empty() {
+        return None;
+    }
+    let shadow = |light: &Light| moon_shadow(point, light, &uniforms.shadow_moons, uniforms.time, uniforms.eclipse_softness);
+    if lights.iter().all(|light| shadow(light) >= 1.0) {
+        return None;
+    }
+    Some(lights.iter().map(|light| {
+        let mut shadowed = light.clone();
+        shadowed.intensity *= shadow(light);
+        shadowed
+    }).collect())
+}
+
+fn ring_band(radius: f32) -> Option<&'static RingBand> {
+    RING_BANDS.iter().find(|band| radius >= band.inner && radius < band.outer)
+}
+
+// Muestras alrededor de la dirección de la luz para la penumbra de los anillos (más el centro)
+const RING_SHADOW_SAMPLES: usize = 8;
+
+// Luz que dejan pasar los anillos a lo largo del rayo `point + t * dir` (t > 0):
+// 1 - opacidad de la banda donde el rayo cruza su plano, o 1 si no la cruza
+fn ring_transmission(point: &Vector3, dir: &Vector3, rings: &RingParams) -> f32 {
+    let n = rings.tilted(Vector3::new(0.0, 1.0, 0.0));
+    let facing = dir.x * n.x + dir.y * n.y + dir.z * n.z;
+    if facing.abs() < 1e-6 {
+        return 1.0;
+    }
+    let t = -(point.x * n.x + point.y * n.y + point.z * n.z) / facing;
+    if t <= 0.0 {
+        return 1.0;
+    }
+    let local = rings.untilted(*point + *dir * t);
+    let radius = rings.band_radius((local.x * local.x + local.z * local.z).sqrt());
+    ring_band(radius).map_or(1.0, |band| 1.0 - band.opacity)
+}
+
+/// Fracción de luz que llega a `point` (sobre el planeta 3) desde `light` a través de los
+/// anillos. Con `rings.shadow_softness > 0` la luz se trata como un disco de ese radio
+/// angular: se promedian rayos en un círculo alrededor de su dirección y el centro, así que
+/// el borde de cada banda se difumina en vez de quedar como una línea dentada.
+fn ring_shadow(point: &Vector3, light: &Light, rings: &RingParams) -> f32 {
+    let dir = light.direction_to(point);
+    let softness = rings.shadow_softness;
+    if softness <= 0.0 {
+        return ring_transmission(point, &dir, rings);
+    }
+
+    // Base perpendicular a la dirección de la luz
+    let axis = if dir.y.abs() < 0.9 { Vector3::new(0.0, 1.0, 0.0) } else { Vector3::new(1.0, 0.0, 0.0) };
+    let u = Vector3::new(dir.y * axis.z - dir.z * axis.y, dir.z * axis.x - dir.x * axis.z, dir.x * axis.y - dir.y * axis.x);
+    let u = u * (1.0 / (u.x * u.x + u.y * u.y + u.z * u.z).sqrt());
+    let v = Vector3::new(dir.y * u.z - dir.z * u.y, dir.z * u.x - dir.x * u.z, dir.x * u.y - dir.y * u.x);
+
+    let mut total = ring_transmission(point, &dir, rings);
+    for i in 0..RING_SHADOW_SAMPLES {
+        let angle = i as f32 / RING_SHADOW_SAMPLES as f32 * 2.0 * std::f32::consts::PI;
+        let offset = (u * angle.cos() + v * angle.sin()) * softness;
+        total += ring_transmission(point, &(dir + offset), rings);
+    }
+    total / (RING_SHADOW_SAMPLES + 1) as f32
+}
+
+// Luces atenuadas por la sombra de los anillos en `point`, o None si ninguna queda tapada
+fn ring_shadowed_lights(point: &Vector3, lights: &[Light], rings: &RingParams) -> Option<Vec<Light>> {
+    let shadows: Vec<f32> = lights.iter().map(|light| ring_shadow(point, light, rings)).collect();
+    if shadows.iter().all(|&shadow| shadow >= 1.0) {
+        return None;
+    }
+    Some(lights.iter().zip(&shadows).map(|(light, &shadow)| {
+        let mut shadowed = light.clone();
+        shadowed.intensity *= shadow;
+        shadowed
+    }).collect())
+}
+
+/// Difuso de cada luz sobre el disco de los anillos, atenuado por la sombra del planeta.
+/// `point`, `ring_normal` y las luces van en coordenadas del planeta, las mismas con
+/// las que se ilumina su superficie
+fn ring_diffuse(point: &Vector3, ring_normal: &Vector3, lights: &[Light], shadow_softness: f32) -> Vector3 {
+    let mut diffuse = Vector3::new(0.0, 0.0, 0.0);
+    for light in lights {
+        let light_dir = light.direction_to(point);
+        let dot = ring_normal.x * light_dir.x + ring_normal.y * light_dir.y + ring_normal.z * light_dir.z;
+        let visibility = planet_shadow(point, light, shadow_softness);
+        diffuse += light.color * (dot.max(0.0) * light.intensity * light.attenuation(point) * visibility);
+    }
+    diffuse
+}
+
+pub fn render_rings(framebuffer: &mut Framebuffer, scratch: &mut RenderScratch, uniforms: &Uniforms, vertex_array: &[Vertex], lights: &[Light]) {
+    let mut ring_uniforms = uniforms.clone();
+    ring_uniforms.render_type = 1;
+    let lights = &object_space_lights(lights, &uniforms.model_matrix);
+
+    let rings = &uniforms.rings;
+    // La normal del disco sigue a su inclinación
+    let ring_normal = rings.tilted(Vector3::new(0.0, 1.0, 0.0));
+
+    render_mesh(framebuffer, scratch, &ring_uniforms, vertex_array, lights, |fragment| {
+        // Posición en el plano de los anillos, llevada a la escala de RING_BANDS
+        let local = rings.untilted(fragment.world_position);
+        let dx = local.x;
+        let dz = local.z;
+        let radius = rings.band_radius((dx * dx + dz * dz).sqrt());
+
+        // Fuera de las bandas (o en un hueco) no se dibuja nada
+        let band = ring_band(radius)?;
+
+        // Rizos finos a lo largo del radio y una variación leve según el ángulo
+        let angle = dz.atan2(dx);
+        let ringlets = fractal_noise(&Vector3::new(radius * 60.0, uniforms.time * 0.05, 0.0), uniforms.noise_quality.octaves(3), uniforms.seed);
+        let grain = gradient_noise(&Vector3::new(angle.cos() * 4.0, angle.sin() * 4.0, radius * 10.0), uniforms.seed);
+        let pattern = (ringlets * 0.8 + grain * 0.2).clamp(0.0, 1.0);
+
+        let base = Vector3::new(0.88, 0.82, 0.65);
+        let dark = Vector3::new(0.65, 0.58, 0.4);
+        let ring_color = base.lerp(dark, pattern * 0.5) * band.brightness;
+
+        let diffuse = ring_diffuse(&fragment.world_position, &ring_normal, lights, uniforms.ring_shadow_softness);
+        let lighting = clamp_channels(diffuse, ambient_light(lights), 1.0); // mínimo ambiente
+
+        // Las bandas poco densas dejan ver lo que hay detrás
+        Some(FragmentOutput::Blend(ring_color * lighting, band.opacity))
+    });
+}
+
+/// Dibuja las lunas del planeta rocoso. Pasan por render_mesh como el planeta y escriben
+/// con Framebuffer::point, así que comparten su buffer de profundidad: el orden de dibujo
+/// no importa y cada uno tapa al otro según quién esté más cerca de la cámara
+pub fn render_moon(framebuffer: &mut Framebuffer, scratch: &mut RenderScratch, uniforms: &Uniforms, vertex_array: &[Vertex], lights: &[Light], moons: &[MoonParams]) {
+    for moon in moons {
+        let mut moon_uniforms = uniforms.clone();
+        moon_uniforms.moon = *moon;
+        render_single_moon(framebuffer, scratch, &moon_uniforms, vertex_array, lights);
+    }
+}
+
+fn render_single_moon(framebuffer: &mut Framebuffer, scratch: &mut RenderScratch, uniforms: &Uniforms, vertex_array: &[Vertex], lights: &[Light]) {
+    let mut moon_uniforms = uniforms.clone();
+    moon_uniforms.render_type = 2;
+    let lights = &object_space_lights(lights, &uniforms.model_matrix);
+
+    let moon_center = uniforms.moon.position(uniforms.time);
+
+    render_mesh(framebuffer, scratch, &moon_uniforms, vertex_array, lights, |fragment| {
+        // Cráteres en coordenadas propias de la luna para que no se deslicen al orbitar
+        let local = (fragment.world_position - moon_center) * (1.0 / uniforms.moon.scale.max(1e-6));
+        let moon_base = Vector3::new(0.65, 0.62, 0.6);
+        let crater_noise = fractal_noise(&Vector3::new(
+            local.x * 8.0,
+            local.y * 8.0,
+            local.z * 8.0
+        ), uniforms.noise_quality.octaves(2), uniforms.seed);
+        let moon_color = if crater_noise > 0.6 {
+            Vector3::new(0.5, 0.48, 0.45)
+        } else {
+            moon_base
+        };
+
+        let lighting = simulate_lighting(&fragment.normal, &fragment.world_position, lights);
+        Some(FragmentOutput::Opaque(moon_color * lighting))
+    });
+}
+
+// Render the cloud shell for the gaseous planet
+pub fn render_clouds(framebuffer: &mut Framebuffer, scratch: &mut RenderScratch, uniforms: &Uniforms, vertex_array: &[Vertex], lights: &[Light]) {
+    let mut cloud_uniforms = uniforms.clone();
+    cloud_uniforms.render_type = 3;
+    let lights = &object_space_lights(lights, &uniforms.model_matrix);
+
+    render_mesh(framebuffer, scratch, &cloud_uniforms, vertex_array, lights, |fragment| {
+        // Las nubes giran más rápido que la superficie para dar paralaje
+        let rotated = rotate_planet_position(&fragment.world_position, uniforms.time, uniforms.rotation_speed + 0.3);
+        // Estiradas a lo largo de las bandas de latitud
+        let density = fractal_noise(&Vector3::new(
+            rotated.x * 6.0,
+            rotated.y * 6.0,
+            rotated.z * 18.0 + uniforms.time * 0.12,
+        ), uniforms.noise_quality.octaves(4), uniforms.seed);
+        let alpha = smoothstep(0.7, 1.0, density);
+
+        if alpha <= 0.0 {
+            return None;
+        }
+
+        let cloud_color = Vector3::new(0.97, 0.95, 0.92);
+        let lighting = simulate_lighting(&fragment.normal, &fragment.world_position, lights);
+        Some(FragmentOutput::Blend(cloud_color * lighting, alpha))
+    });
+}
+
+/// Dibuja la fuente de luz como una esfera pequeña emisiva (sin iluminar) de radio `radius`
+/// en `center`, en coordenadas de mundo. Una esfera se ve igual desde cualquier lado, así que
+/// siempre "mira" a la cámara; el centro es blanco amarillento y el borde más anaranjado,
+/// teñidos por `tint` (el color de la luz). Escribe profundidad, así que el planeta la tapa
+pub fn render_sun(framebuffer: &mut Framebuffer, scratch: &mut RenderScratch, uniforms: &Uniforms, vertex_array: &[Vertex], center: Vector3, radius: f32, tint: Vector3) {
+    let mut sun_uniforms = uniforms.clone();
+    sun_uniforms.render_type = 4;
+    sun_uniforms.model_matrix = create_model_matrix(center, radius, Vector3::zero());
+
+    let mut to_camera = uniforms.camera_position - center;
+    let distance = (to_camera.x * to_camera.x + to_camera.y * to_camera.y + to_camera.z * to_camera.z).sqrt();
+    if distance > 0.0 {
+        to_camera *= 1.0 / distance;
+    }
+
+    render_mesh(framebuffer, scratch, &sun_uniforms, vertex_array, &[], |fragment| {
+        // 1 en el centro del disco visible, 0 en el borde
+        let n = &fragment.normal;
+        let facing = (n.x * to_camera.x + n.y * to_camera.y + n.z * to_camera.z).max(0.0);
+        let core = Vector3::new(1.0, 1.0, 0.9);
+        let rim = Vector3::new(1.0, 0.75, 0.3);
+        let glow = rim.lerp(core, facing.sqrt());
+        Some(FragmentOutput::Opaque(Vector3::new(glow.x * tint.x, glow.y * tint.y, glow.z * tint.z)))
+    });
+}
+
+// Casillas del tablero por unidad de UV; u da la vuelta a la esfera, así que lleva el doble
+const CHECKER_SCALE: f32 = 16.0;
+
+/// Tablero de ajedrez sobre las coordenadas UV: `scale` casillas por unidad en v y el doble
+/// en u. Sirve para ver a simple vista si las UV del modelo están bien (casillas cuadradas,
+/// sin saltos ni estiramientos)
+pub fn sample_checker(uv: &Vector2, scale: f32) -> Vector3 {
+    let cell = (uv.x * scale * 2.0).floor() as i32 + (uv.y * scale).floor() as i32;
+    if cell.rem_euclid(2) == 0 {
+        Vector3::new(0.9, 0.9, 0.9)
+    } else {
+        Vector3::new(0.15, 0.15, 0.2)
+    }
+}
+
+/// Tone mapping ACES (aproximación de Narkowicz): lleva un color HDR a [0, 1]
+/// con una curva suave, conservando detalle en los brillos por encima de 1.0
+pub fn tone_map(color: Vector3) -> Vector3 {
+    let aces = |x: f32| {
+        let x = x.max(0.0);
+        ((x * (2.51 * x + 0.03)) / (x * (2.43 * x + 0.59) + 0.14)).clamp(0.0, 1.0)
+    };
+    Vector3::new(aces(color.x), aces(color.y), aces(color.z))
+}
+
+pub fn fragment_shader(fragment: &Fragment, uniforms: &Uniforms, lights: &[Light]) -> Vector3 {
+    // Gouraud y plano ya traen el color iluminado desde el rasterizador, y el modo de
+    // colores de vértice el color interpolado de la malla
+    match uniforms.shading_mode {
+        ShadingMode::Procedural | ShadingMode::Textured => {}
+        ShadingMode::Checker => {
+            let lighting = simulate_lighting(&fragment.normal, &fragment.world_position, lights);
+            return sample_checker(&fragment.tex_coords, CHECKER_SCALE) * lighting;
+        }
+        _ => return fragment.color,
+    }
+
+    let pos = fragment.world_position;
+    let time = uniforms.time;
+    let planet_type = uniforms.planet_type;
+    
+    // Relieve del planeta rocoso: la normal se perturba con el mapa de normales
+    let normal = if planet_type == 0 {
+        let tangent_normal = sample_normal_map(&fragment.tex_coords, NORMAL_MAP_STRENGTH, uniforms.noise_quality, uniforms.seed);
+        perturb_normal(&fragment.normal, &fragment.tangent, &fragment.bitangent, &tangent_normal)
+    } else {
+        fragment.normal
+    };
+
+    // Eclipse: las lunas que pasan frente a la luz la atenúan solo en este fragmento
+    let eclipsed = if planet_type == 0 { eclipsed_lights(&pos, lights, uniforms) } else { None };
+    let lights = eclipsed.as_deref().unwrap_or(lights);
+
+    // Los anillos del planeta 3 proyectan sus bandas sobre la superficie
+    let ring_shadowed = if planet_type == 3 { ring_shadowed_lights(&pos, lights, &uniforms.rings) } else { None };
+    let lights = ring_shadowed.as_deref().unwrap_or(lights);
+
+    // Con un mapa de color cargado, el planeta rocoso lo usa en vez del color procedural
+    let color_map = match uniforms.shading_mode {
+        ShadingMode::Textured => uniforms.color_map.as_deref(),
+        _ => None,
+    };
+
+    let model = uniforms.lighting_model.unwrap_or_else(|| planet_lighting_model(planet_type));
+    let color = match planet_type {
+        0 => match color_map {
+            Some(map) => shade(model, map.sample(fragment.tex_coords.x, fragment.tex_coords.y), &normal, &pos, lights),
+            None => rocky_planet_color(&pos, &normal, time, uniforms.rotation_speed, uniforms.noise_quality, uniforms.seed, &uniforms.craters, uniforms.ao_strength, model, lights),
+        },
+        1 => gaseous_planet_color(&pos, &normal, time, uniforms.rotation_speed, model, lights),
+        2 => biolum_planet_color(&pos, &normal, time, uniforms.rotation_speed, uniforms.noise_quality, uniforms.seed, model, lights), // ¡Planeta de ciencia ficción!
+        3 => ringed_planet_color(&pos, &normal, time, uniforms.rotation_speed, model, lights),
+        4 => ice_planet_color(&pos, &normal, time, uniforms.rotation_speed, uniforms.noise_quality, uniforms.seed, uniforms.ao_strength, model, lights),
+        5 => lava_planet_color(&pos, &normal, time, uniforms.rotation_speed, uniforms.noise_quality, uniforms.seed, model, lights),
+        _ => Vector3::new(0.5, 0.5, 0.5),
+    };
+
+    // Reflejo especular con la normal interpolada del fragmento
+    let (shininess, strength) = planet_shininess(planet_type);
+    let specular = specular_lighting(&normal, &pos, &uniforms.camera_position, lights, shininess) * strength;
+    let mut color = color + specular;
+
+    // Halo atmosférico con la normal interpolada del fragmento
+    if let Some((atmosphere_color, power)) = planet_atmosphere(planet_type) {
+        let rim = rim_term(&normal, &pos, &uniforms.camera_position, power);
+        color += atmosphere_color * rim;
+    }
+
+    // Las zonas emisivas pasan de 1.0: el tone mapping las comprime en vez de recortarlas
+    if uniforms.tone_mapping {
+        color = tone_map(color);
+    }
+
+    Vector3::new(
+        color.x.max(0.0).min(1.0),
+        color.y.max(0.0).min(1.0),
+        color.z.max(0.0).min(1.0),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::uniforms_for;
+
+    // Luz de frente a un punto del ecuador con normal +X
+    fn facing_light(intensity: f32) -> Light {
+        let mut light = Light::new(Vector3::new(10.0, 0.0, 0.0));
+        light.intensity = intensity;
+        light
+    }
+
+    #[test]
+    fn two_identical_lights_double_the_brightness() {
+        let normal = Vector3::new(1.0, 0.0, 0.0);
+        let point = Vector3::new(1.0, 0.0, 0.0);
+
+        let one = simulate_lighting(&normal, &point, &[facing_light(0.3)]);
+        let two = simulate_lighting(&normal, &point, &[facing_light(0.3), facing_light(0.3)]);
+        assert!((one.x - 0.3).abs() < 1e-5);
+        assert!((two.x - 0.6).abs() < 1e-5);
+
+        // La suma se recorta en 1
+        let bright = simulate_lighting(&normal, &point, &[facing_light(0.7), facing_light(0.7)]);
+        assert_eq!(bright, Vector3::new(1.0, 1.0, 1.0));
+    }
+
+    #[test]
+    fn ring_shadow_falls_on_the_night_side_of_a_tilted_planet() {
+        // Inclinado 98° como Urano: la sombra sobre los anillos y el lado de noche del
+        // planeta tienen que salir de la misma luz, en las mismas coordenadas
+        let mut uniforms = uniforms_for(4);
+        uniforms.model_matrix = create_model_matrix(Vector3::new(0.0, 0.0, 0.0), 1.0, Vector3::new(0.0, 0.0, 98f32.to_radians()));
+        let lights = object_space_lights(&[Light::new(Vector3::new(0.0, 10.0, 3.0))], &uniforms.model_matrix);
+
+        let mut shadowed = 0;
+        for step in 0..360 {
+            let angle = (step as f32).to_radians();
+            let point = uniforms.rings.tilted(Vector3::new(2.0 * angle.cos(), 0.0, 2.0 * angle.sin()));
+            if planet_shadow(&point, &lights[0], 0.0) > 0.0 {
+                continue;
+            }
+            shadowed += 1;
+            // El punto del planeta bajo la sombra queda de noche
+            let normal = Vector3::new(point.x / 2.0, point.y / 2.0, point.z / 2.0);
+            let vertex = vertex_shader(&Vertex::new(normal, normal, Vector2::new(0.0, 0.0)), &uniforms);
+            let lit = diffuse_lighting(&vertex.transformed_normal, &vertex.position, &lights);
+            assert!(lit == 0.0, "iluminado {} a {}°", lit, step);
+        }
+        assert!(shadowed > 0);
+    }
+
+    #[test]
+    fn rings_and_pole_agree_on_the_lit_side_of_a_tilted_planet() {
+        // Los anillos van en el ecuador: su cara de arriba mira al mismo lado que el polo
+        // norte, así que con el planeta inclinado se iluminan o no junto con él
+        let mut uniforms = uniforms_for(4);
+        uniforms.model_matrix = create_model_matrix(Vector3::new(0.0, 0.0, 0.0), 1.0, Vector3::new(0.0, 0.0, 98f32.to_radians()));
+        let pole = vertex_shader(&Vertex::new(Vector3::new(0.0, 1.0, 0.0), Vector3::new(0.0, 1.0, 0.0), Vector2::new(0.0, 0.0)), &uniforms);
+        let ring_normal = uniforms.rings.tilted(Vector3::new(0.0, 1.0, 0.0));
+
+        for position in [Vector3::new(10.0, 2.0, 1.0), Vector3::new(-10.0, 2.0, 1.0), Vector3::new(3.0, -10.0, 2.0), Vector3::new(-10.0, -2.0, -3.0)] {
+            let lights = object_space_lights(&[Light::new(position)], &uniforms.model_matrix);
+            let pole_lit = diffuse_lighting(&pole.transformed_normal, &pole.position, &lights) > 0.0;
+            // Un punto del anillo fuera de la sombra del planeta
+            let point = (0..360)
+                .map(|step| {
+                    let angle = (step as f32).to_radians();
+                    uniforms.rings.tilted(Vector3::new(2.0 * angle.cos(), 0.0, 2.0 * angle.sin()))
+                })
+                .find(|point| planet_shadow(point, &lights[0], 0.0) == 1.0)
+                .unwrap();
+            let ring_lit = ring_diffuse(&point, &ring_normal, &lights, 0.0).x > 0.0;
+            assert_eq!(ring_lit, pole_lit, "luz en {:?}", position);
+        }
+    }
+
+    #[test]
+    fn specular_highlight_follows_a_tilted_planet() {
+        // Cámara y luz en el mismo lugar: el brillo máximo cae donde la normal, ya en el
+        // mundo, apunta hacia ellas. En coordenadas del objeto ese punto es otro
+        let model = create_model_matrix(Vector3::new(0.0, 0.0, 0.0), 1.0, Vector3::new(0.6, 0.0, 98f32.to_radians()));
+        let eye = Vector3::new(0.0, 2.0, 8.0);
+        let lights = object_space_lights(&[Light::new(eye)], &model);
+        let camera = object_space_point(&eye, &model);
+
+        let len = (camera.x * camera.x + camera.y * camera.y + camera.z * camera.z).sqrt();
+        let normal = Vector3::new(camera.x / len, camera.y / len, camera.z / len);
+        let peak = specular_lighting(&normal, &normal, &camera, &lights, 64.0);
+        let expected = lights[0].intensity * lights[0].attenuation(&normal);
+        assert!((peak.x - expected).abs() < 1e-3, "{} != {}", peak.x, expected);
+
+        // Con la cámara del mundo sin convertir el brillo se pierde
+        let world = specular_lighting(&normal, &normal, &eye, &lights, 64.0);
+        assert!(world.x < expected * 0.5, "{}", world.x);
+    }
+
+    #[test]
+    fn rim_hugs_the_silhouette_of_a_tilted_planet() {
+        let model = create_model_matrix(Vector3::new(0.0, 0.0, 0.0), 1.0, Vector3::new(0.6, 0.0, 98f32.to_radians()));
+        let camera = object_space_point(&Vector3::new(0.0, 0.0, 8.0), &model);
+
+        // El punto que mira a la cámara no tiene halo y el del borde lo tiene completo
+        let facing = object_space_point(&Vector3::new(0.0, 0.0, 1.0), &model);
+        let edge = object_space_point(&Vector3::new(1.0, 0.0, 0.0), &model);
+        assert!(rim_term(&facing, &facing, &camera, 3.0) < 1e-4);
+        assert!((rim_term(&edge, &edge, &camera, 3.0) - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn moon_stays_on_its_orbit() {
+        let moon = MoonParams { orbit_radius: 3.1, wobble: 0.35, ..MoonParams::default() };
+        for step in 0..200 {
+            let position = moon.position(step as f32 * 0.37);
+            // Sin inclinación el radio se mide en el plano XZ; Y solo lleva el vaivén
+            let horizontal = (position.x * position.x + position.z * position.z).sqrt();
+            assert!((horizontal - moon.orbit_radius).abs() < 1e-4, "radio {} en el paso {}", horizontal, step);
+            assert!(position.y.abs() <= moon.wobble + 1e-6);
+        }
+    }
+}
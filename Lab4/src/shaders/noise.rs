@@ -0,0 +1,75 @@
+// shaders/noise.rs
+use raylib::prelude::*;
+
+// Direcciones de gradiente de Perlin: los puntos medios de las 12 aristas del cubo
+const GRADIENTS: [(f32, f32, f32); 12] = [
+    (1.0, 1.0, 0.0), (-1.0, 1.0, 0.0), (1.0, -1.0, 0.0), (-1.0, -1.0, 0.0),
+    (1.0, 0.0, 1.0), (-1.0, 0.0, 1.0), (1.0, 0.0, -1.0), (-1.0, 0.0, -1.0),
+    (0.0, 1.0, 1.0), (0.0, -1.0, 1.0), (0.0, 1.0, -1.0), (0.0, -1.0, -1.0),
+];
+
+// Hash entero de un punto de la rejilla; no se repite cuando las coordenadas
+// combinadas coinciden, y la semilla lo cambia por completo
+pub(super) fn lattice_hash(x: i32, y: i32, z: i32, seed: u32) -> u32 {
+    let mut h = seed.wrapping_mul(0x9E37_79B9)
+        ^ (x as u32).wrapping_mul(0x8DA6_B343)
+        ^ (y as u32).wrapping_mul(0xD816_3841)
+        ^ (z as u32).wrapping_mul(0xCB1A_B31F);
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x7FEB_352D);
+    h ^= h >> 15;
+    h = h.wrapping_mul(0x846C_A68B);
+    h ^= h >> 16;
+    h
+}
+
+/// Ruido de gradiente 3D (tipo Perlin): cada punto de la rejilla tiene un gradiente
+/// pseudoaleatorio y el valor es la mezcla de sus productos punto con el desplazamiento.
+/// Se interpola con smoothstep y no forma bloques alineados a los ejes. La semilla elige otro juego de gradientes: misma semilla, mismo ruido.
+/// Devuelve un valor en [0, 1] centrado en 0.5.
+pub fn gradient_noise(pos: &Vector3, seed: u32) -> f32 {
+    let ix = pos.x.floor() as i32;
+    let iy = pos.y.floor() as i32;
+    let iz = pos.z.floor() as i32;
+
+    let fx = pos.x - pos.x.floor();
+    let fy = pos.y - pos.y.floor();
+    let fz = pos.z - pos.z.floor();
+
+    // Smoothstep interpolation
+    let u = fx * fx * (3.0 - 2.0 * fx);
+    let v = fy * fy * (3.0 - 2.0 * fy);
+    let w = fz * fz * (3.0 - 2.0 * fz);
+
+    // Producto punto del gradiente de la esquina (i, j, k) con el vector hacia el punto
+    let g = |i: i32, j: i32, k: i32| -> f32 {
+        let (gx, gy, gz) = GRADIENTS[(lattice_hash(ix + i, iy + j, iz + k, seed) % 12) as usize];
+        gx * (fx - i as f32) + gy * (fy - j as f32) + gz * (fz - k as f32)
+    };
+
+    let x1 = g(0, 0, 0) + (g(1, 0, 0) - g(0, 0, 0)) * u;
+    let x2 = g(0, 1, 0) + (g(1, 1, 0) - g(0, 1, 0)) * u;
+    let x3 = g(0, 0, 1) + (g(1, 0, 1) - g(0, 0, 1)) * u;
+    let x4 = g(0, 1, 1) + (g(1, 1, 1) - g(0, 1, 1)) * u;
+
+    let y1 = x1 + (x2 - x1) * v;
+    let y2 = x3 + (x4 - x3) * v;
+
+    // A [0, 1] con una dispersión parecida a la de un ruido de valor (el gradiente varía
+    // menos), para los umbrales de cada planeta; los extremos raros se recortan
+    ((y1 + (y2 - y1) * w) * 0.75 + 0.5).clamp(0.0, 1.0)
+}
+
+pub(super) fn fractal_noise(pos: &Vector3, octaves: i32, seed: u32) -> f32 {
+    let mut value = 0.0;
+    let mut amplitude = 1.0;
+    let mut frequency = 1.0;
+    let mut weight = 1.0;
+    for _ in 0..octaves {
+        value += gradient_noise(&Vector3::new(pos.x * frequency, pos.y * frequency, pos.z * frequency), seed) * amplitude * weight;
+        amplitude *= 0.5;
+        frequency *= 2.0;
+        weight *= 0.7;
+    }
+    value
+}
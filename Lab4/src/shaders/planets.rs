@@ -0,0 +1,418 @@
+// shaders/planets.rs
+use raylib::prelude::*;
+use crate::{NoiseQuality, LightingModel};
+use crate::light::Light;
+use super::{Lerp, smoothstep, night_side, shade};
+use super::noise::{fractal_noise, lattice_hash};
+
+/// Velocidad de giro base de cada planeta (radianes por segundo)
+pub fn planet_rotation_speed(planet_type: i32) -> f32 {
+    match planet_type {
+        0 => 0.25, // rocoso
+        1 => 1.3,  // gaseoso, gira rápido
+        2 => 0.6,
+        3 => 0.5,
+        4 => 0.3,
+        5 => 0.4,
+        _ => 0.0,
+    }
+}
+
+/// Inclinación axial base de cada planeta (grados). El eje de giro es Y en espacio
+/// de objeto y los anillos están en el plano XZ, así que ambos se inclinan juntos
+pub fn planet_axial_tilt(planet_type: i32) -> f32 {
+    match planet_type {
+        0 => 25.0, // como Marte
+        1 => 3.0,  // como Júpiter, casi derecho
+        2 => 15.0,
+        3 => 27.0, // como Saturno
+        4 => 8.0,
+        5 => 12.0,
+        _ => 0.0,
+    }
+}
+
+/// Brillo especular por tipo de planeta: (shininess, intensidad del especular)
+pub fn planet_shininess(planet_type: i32) -> (f32, f32) {
+    match planet_type {
+        0 => (16.0, 0.1),  // rocoso, casi mate
+        1 => (4.0, 0.05),  // gaseoso, opaco
+        2 => (32.0, 0.3),  // océanos bioluminiscentes
+        3 => (12.0, 0.1),
+        4 => (96.0, 0.8),  // hielo, muy brillante
+        5 => (24.0, 0.15), // basalto vítreo
+        _ => (8.0, 0.0),
+    }
+}
+
+/// Modelo de iluminación por tipo de planeta (el de siempre de cada uno)
+pub fn planet_lighting_model(planet_type: i32) -> LightingModel {
+    match planet_type {
+        2 => LightingModel::Soft,    // bioluminiscente: la noche llega de a poco y deja ver la flora
+        4 => LightingModel::Fresnel, // hielo: bordes claros y brillos que pasan de 1
+        _ => LightingModel::Lambert,
+    }
+}
+
+/// Atmósfera por tipo de planeta: (color del halo, exponente del borde)
+pub fn planet_atmosphere(planet_type: i32) -> Option<(Vector3, f32)> {
+    match planet_type {
+        1 => Some((Vector3::new(0.55, 0.7, 1.0), 3.0)), // gaseoso: halo azulado
+        2 => Some((Vector3::new(0.25, 0.9, 0.55), 2.5)), // bioluminiscente: halo verde
+        _ => None,
+    }
+}
+
+// Latitud a partir de z/r. El redondeo puede dejar el cociente apenas fuera de [-1, 1]
+// y asin daría NaN, así que se recorta antes
+fn latitude(z_over_r: f32) -> f32 {
+    z_over_r.clamp(-1.0, 1.0).asin()
+}
+
+pub(super) fn rotate_planet_position(pos: &Vector3, time: f32, speed: f32) -> Vector3 {
+    let angle = time * speed;
+    let cos_a = angle.cos();
+    let sin_a = angle.sin();
+    Vector3::new(
+        pos.x * cos_a - pos.z * sin_a,
+        pos.y,
+        pos.x * sin_a + pos.z * cos_a
+    )
+}
+
+/// Tamaño de los cráteres: radio = mínimo + (máximo - mínimo) * u^exponente con u uniforme,
+/// así que con exponente mayor que 1 la mayoría son pequeños y unos pocos grandes
+#[derive(Clone, Copy, Debug)]
+pub struct CraterSizes {
+    pub min_radius: f32, // en radios del planeta
+    pub max_radius: f32, // mayor o igual que min_radius
+    pub exponent: f32,   // 1: tamaños parejos; más alto: más cráteres pequeños
+}
+
+impl Default for CraterSizes {
+    fn default() -> Self {
+        CraterSizes {
+            min_radius: 0.05,
+            max_radius: 0.22,
+            exponent: 3.0,
+        }
+    }
+}
+
+/// Cráteres (centro sobre la esfera unitaria, radio) generados a partir de la semilla,
+/// con los tamaños por defecto de `CraterSizes`.
+/// Los centros se reparten de forma uniforme sobre la esfera; la misma semilla
+/// siempre da los mismos cráteres.
+pub fn generate_craters(seed: u32, count: usize) -> Vec<(Vector3, f32)> {
+    generate_craters_with_sizes(seed, count, &CraterSizes::default())
+}
+
+/// Como `generate_craters`, con los radios repartidos según `sizes`
+pub fn generate_craters_with_sizes(seed: u32, count: usize, sizes: &CraterSizes) -> Vec<(Vector3, f32)> {
+    let random = |i: usize, k: i32| lattice_hash(i as i32, k, 0, seed ^ 0x5EED_C7A7) as f32 / u32::MAX as f32;
+    (0..count)
+        .map(|i| {
+            let z = random(i, 0) * 2.0 - 1.0;
+            let phi = random(i, 1) * 2.0 * std::f32::consts::PI;
+            let ring = (1.0 - z * z).max(0.0).sqrt();
+            let center = Vector3::new(ring * phi.cos(), ring * phi.sin(), z);
+            let radius = sizes.min_radius + (sizes.max_radius - sizes.min_radius) * random(i, 2).powf(sizes.exponent);
+            (center, radius)
+        })
+        .collect()
+}
+
+// 0: Rocky (Mars-like)
+#[allow(clippy::too_many_arguments)]
+pub(super) fn rocky_planet_color(pos: &Vector3, normal: &Vector3, time: f32, speed: f32, quality: NoiseQuality, seed: u32, craters: &[(Vector3, f32)], ao_strength: f32, model: LightingModel, lights: &[Light]) -> Vector3 {
+    let rotated = rotate_planet_position(pos, time, speed);
+    let base_noise = fractal_noise(&rotated, quality.octaves(4), seed);
+    let detail = fractal_noise(&Vector3::new(rotated.x * 8.0, rotated.y * 8.0, rotated.z * 8.0), quality.octaves(2), seed);
+    let elevation = (base_noise + detail * 0.3) * 0.5 + 0.5;
+
+    let low = Vector3::new(0.55, 0.25, 0.15);
+    let high = Vector3::new(0.75, 0.45, 0.25);
+    let crater = Vector3::new(0.2, 0.15, 0.1);
+
+    let mut color = if elevation < 0.3 {
+        low
+    } else if elevation > 0.7 {
+        high
+    } else {
+        low.lerp(high, (elevation - 0.3) / 0.4)
+    };
+
+    // Cráteres
+    let mut crater_depth: f32 = 0.0;
+    for (c, radius) in craters {
+        let d = ((rotated.x - c.x).powi(2) + (rotated.y - c.y).powi(2) + (rotated.z - c.z).powi(2)).sqrt();
+        if d < *radius {
+            let blend = (1.0 - (d / radius).min(1.0)).powi(2);
+            color = color.lerp(crater, blend * 0.8);
+            crater_depth = crater_depth.max(blend);
+        }
+    }
+
+    // Oclusión: los valles (elevación baja) y el fondo de los cráteres reciben menos luz
+    let cavity = (1.0 - smoothstep(0.2, 0.55, elevation)).max(crater_depth);
+    shade(model, color, normal, pos, lights) * ambient_occlusion(cavity, ao_strength)
+}
+
+/// Oclusión ambiental aproximada: cuánta luz conserva un punto según qué tan hundido está
+/// (`cavity` en [0, 1], del ruido de elevación que ya calculó el planeta, sin muestras extra).
+/// `strength` 0 la desactiva y 1 deja negro el fondo de las cavidades
+fn ambient_occlusion(cavity: f32, strength: f32) -> f32 {
+    1.0 - strength.clamp(0.0, 1.0) * cavity.clamp(0.0, 1.0)
+}
+
+// 1: Gaseous (Jupiter-like)
+pub(super) fn gaseous_planet_color(pos: &Vector3, normal: &Vector3, time: f32, speed: f32, model: LightingModel, lights: &[Light]) -> Vector3 {
+    let rotated = rotate_planet_position(pos, time, speed);
+    let r = (rotated.x.powi(2) + rotated.y.powi(2) + rotated.z.powi(2)).sqrt().max(0.001);
+    let lat = latitude(rotated.z / r);
+
+    let band1 = (lat * 9.0 + time * 0.25).sin().abs();
+    let band2 = (lat * 14.0 + time * 0.35 + 0.7).cos().abs();
+
+    let mut color = Vector3::new(0.92, 0.82, 0.65);
+
+    if band1 > 0.75 {
+        color = color.lerp(Vector3::new(0.55, 0.35, 0.2), 0.5);
+    }
+    if band2 > 0.8 {
+        color = color.lerp(Vector3::new(0.4, 0.3, 0.6), 0.4);
+    }
+
+    // Tormenta animada
+    let storm_phase = time * 0.1;
+    let storm_x = rotated.x + 0.35 + storm_phase.sin() * 0.05;
+    let storm_y = rotated.y - 0.2 + storm_phase.cos() * 0.03;
+    let storm_d = (storm_x * storm_x + storm_y * storm_y).sqrt();
+    if storm_d < 0.22 {
+        let blend = (1.0 - storm_d / 0.22).powi(2);
+        color = color.lerp(Vector3::new(0.88, 0.25, 0.18), blend * 0.7);
+    }
+
+    // Las nubes van en su propia capa (render_clouds)
+
+    shade(model, color, normal, pos, lights)
+}
+
+// 2: Sci-fi Bioluminescent Planet
+#[allow(clippy::too_many_arguments)]
+pub(super) fn biolum_planet_color(pos: &Vector3, normal: &Vector3, time: f32, speed: f32, quality: NoiseQuality, seed: u32, model: LightingModel, lights: &[Light]) -> Vector3 {
+    let rotated = rotate_planet_position(pos, time, speed);
+    let r = (rotated.x.powi(2) + rotated.y.powi(2) + rotated.z.powi(2)).sqrt().max(0.001);
+    let lat = latitude(rotated.z / r);
+    let _lon = rotated.y.atan2(rotated.x); // no se usa, pero lo dejamos comentado
+
+    let terrain = fractal_noise(&rotated, quality.octaves(4), seed);
+    let elevation = terrain * 0.5 + 0.5;
+
+    let ocean = Vector3::new(0.02, 0.05, 0.15);
+    let land = Vector3::new(0.1, 0.3, 0.1);
+    let glow_plants = Vector3::new(0.2, 0.8, 0.4); // verde brillante
+
+    let mut color = if elevation < 0.4 {
+        ocean
+    } else {
+        land
+    };
+
+    // Flora bioluminiscente en zonas altas
+    let glow_noise = fractal_noise(&Vector3::new(rotated.x * 6.0, rotated.y * 6.0, rotated.z * 6.0), quality.octaves(3), seed);
+    let is_glowing = glow_noise > 0.6 && elevation > 0.5;
+    if is_glowing {
+        color = color.lerp(glow_plants, 0.7);
+    }
+
+    // Polos helados
+    if lat.abs() > 1.1 {
+        color = Vector3::new(0.85, 0.9, 1.0);
+    }
+
+    // Iluminación + emisión nocturna
+    let night = night_side(normal, pos, lights);
+    let mut final_color = shade(model, color, normal, pos, lights);
+    if is_glowing {
+        final_color = final_color + glow_plants * (0.3 * night); // brilla en la noche
+    }
+
+    // Luces de ciudades: puntos cálidos en tierra firme, fuera de los polos,
+    // que aparecen a medida que la zona entra en la noche
+    if elevation >= 0.4 && lat.abs() < 0.95 {
+        let city_noise = fractal_noise(&Vector3::new(rotated.x * 18.0, rotated.y * 18.0, rotated.z * 18.0), quality.octaves(2), seed);
+        if city_noise > 0.75 {
+            let city_glow = Vector3::new(1.0, 0.8, 0.35);
+            let strength = ((city_noise - 0.75) / 0.25).min(1.0);
+            final_color = final_color + city_glow * (strength * night * 0.8);
+        }
+    }
+
+    final_color
+}
+
+// 3: Ringed Planet (Saturn-like)
+pub(super) fn ringed_planet_color(pos: &Vector3, normal: &Vector3, time: f32, speed: f32, model: LightingModel, lights: &[Light]) -> Vector3 {
+    let rotated = rotate_planet_position(pos, time, speed);
+    let r = (rotated.x.powi(2) + rotated.y.powi(2) + rotated.z.powi(2)).sqrt().max(0.001);
+    let lat = latitude(rotated.z / r);
+
+    let base = Vector3::new(0.75, 0.65, 0.5);
+    let bands = (lat * 7.0 + time * 0.08).sin().abs();
+    let color = base.lerp(Vector3::new(0.85, 0.75, 0.4), bands * 0.35);
+
+    shade(model, color, normal, pos, lights)
+}
+
+// 4: Ice Crystal Planet
+#[allow(clippy::too_many_arguments)]
+pub(super) fn ice_planet_color(pos: &Vector3, normal: &Vector3, time: f32, speed: f32, quality: NoiseQuality, seed: u32, ao_strength: f32, model: LightingModel, lights: &[Light]) -> Vector3 {
+    let rotated = rotate_planet_position(pos, time, speed);
+    let noise_val = fractal_noise(&rotated, quality.octaves(5), seed);
+    let fractures = fractal_noise(&Vector3::new(rotated.x * 10.0, rotated.y * 10.0, rotated.z * 10.0 + time), quality.octaves(3), seed);
+
+    let base_ice = Vector3::new(0.85, 0.95, 1.0);
+    let deep_ice = Vector3::new(0.6, 0.8, 0.95);
+    let crystal_core = Vector3::new(0.9, 0.98, 1.0);
+
+    let color = if noise_val < 0.3 {
+        deep_ice
+    } else if fractures > 0.7 {
+        crystal_core
+    } else {
+        base_ice
+    };
+
+    // Oclusión: el hielo profundo y las fracturas quedan hundidos
+    let cavity = (1.0 - smoothstep(0.1, 0.4, noise_val)).max(smoothstep(0.6, 0.85, fractures));
+
+    // El efecto de refracción (bordes más claros) lo pone el modelo Fresnel
+    shade(model, color, normal, pos, lights) * ambient_occlusion(cavity, ao_strength)
+}
+
+// 5: Lava / Volcanic Planet
+#[allow(clippy::too_many_arguments)]
+pub(super) fn lava_planet_color(pos: &Vector3, normal: &Vector3, time: f32, speed: f32, quality: NoiseQuality, seed: u32, model: LightingModel, lights: &[Light]) -> Vector3 {
+    let rotated = rotate_planet_position(pos, time, speed);
+
+    // Placas de basalto; las grietas quedan donde el ruido pasa por su valor medio (~0.75)
+    let plates = fractal_noise(&Vector3::new(rotated.x * 3.0, rotated.y * 3.0, rotated.z * 3.0), quality.octaves(4), seed);
+    let crack = (1.0 - (plates - 0.75).abs() * 8.0).max(0.0);
+    let detail = fractal_noise(&Vector3::new(rotated.x * 12.0, rotated.y * 12.0, rotated.z * 12.0), quality.octaves(2), seed);
+
+    let basalt = Vector3::new(0.08, 0.06, 0.05);
+    let ash = Vector3::new(0.2, 0.17, 0.15);
+    let rock = basalt.lerp(ash, detail);
+
+    let lava_hot = Vector3::new(1.0, 0.75, 0.2);
+    let lava_cool = Vector3::new(0.8, 0.15, 0.02);
+    let lava = lava_cool.lerp(lava_hot, crack * crack);
+
+    // La roca sólo se ve con luz; la lava emite siempre y resalta más de noche
+    let night = night_side(normal, pos, lights);
+
+    // Pulso lento, desfasado según la zona para que no lata todo a la vez
+    let pulse = 0.85 + 0.15 * (time * 2.0 + detail * 6.0).sin();
+    let emission = crack * pulse * (0.6 + 0.4 * night);
+
+    shade(model, rock, normal, pos, lights) * (1.0 - crack) + lava * emission
+}
+
+// Qué tanto inclina el mapa de normales a la normal de la superficie
+pub(super) const NORMAL_MAP_STRENGTH: f32 = 0.6;
+
+/// Mapa de normales procedural: alturas de `fractal_noise` sobre las coordenadas UV,
+/// derivadas con diferencias finitas. Devuelve la normal en espacio tangente
+/// (x a lo largo de u, y a lo largo de v, z hacia afuera de la superficie).
+/// u da la vuelta a la esfera, así que se muestrea sobre un círculo (cos 2πu, sin 2πu):
+/// u = 0 y u = 1 caen en el mismo punto del ruido y no queda costura.
+pub(super) fn sample_normal_map(tex_coords: &Vector2, strength: f32, quality: NoiseQuality, seed: u32) -> Vector3 {
+    // Más frecuencia en u porque la esfera da la vuelta completa en u y media en v.
+    // El círculo mide scale.x de largo: la celda de ruido no cambia de tamaño en u
+    let scale = Vector2::new(64.0, 32.0);
+    let radius = scale.x / (2.0 * std::f32::consts::PI);
+    let height = |u: f32, v: f32| {
+        let (sin_u, cos_u) = (u * 2.0 * std::f32::consts::PI).sin_cos();
+        fractal_noise(&Vector3::new(cos_u * radius, sin_u * radius, v * scale.y), quality.octaves(3), seed)
+    };
+
+    let eps = 1.0 / 1024.0;
+    let h = height(tex_coords.x, tex_coords.y);
+    // Pendiente por celda de ruido para que `strength` no dependa de la escala
+    let dh_du = (height(tex_coords.x + eps, tex_coords.y) - h) / (eps * scale.x);
+    let dh_dv = (height(tex_coords.x, tex_coords.y + eps) - h) / (eps * scale.y);
+
+    let n = Vector3::new(-dh_du * strength, -dh_dv * strength, 1.0);
+    let len = (n.x * n.x + n.y * n.y + n.z * n.z).sqrt();
+    Vector3::new(n.x / len, n.y / len, n.z / len)
+}
+
+/// Lleva una normal del espacio tangente al del objeto con la matriz TBN.
+/// Sin marco tangente (tangente nula) se devuelve la normal sin cambios.
+pub(super) fn perturb_normal(normal: &Vector3, tangent: &Vector3, bitangent: &Vector3, tangent_normal: &Vector3) -> Vector3 {
+    if tangent.x == 0.0 && tangent.y == 0.0 && tangent.z == 0.0 {
+        return *normal;
+    }
+    let n = Vector3::new(
+        tangent.x * tangent_normal.x + bitangent.x * tangent_normal.y + normal.x * tangent_normal.z,
+        tangent.y * tangent_normal.x + bitangent.y * tangent_normal.y + normal.y * tangent_normal.z,
+        tangent.z * tangent_normal.x + bitangent.z * tangent_normal.y + normal.z * tangent_normal.z,
+    );
+    let len = (n.x * n.x + n.y * n.y + n.z * n.z).sqrt();
+    if len > 0.0 {
+        Vector3::new(n.x / len, n.y / len, n.z / len)
+    } else {
+        *normal
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_finite(color: &Vector3) -> bool {
+        color.x.is_finite() && color.y.is_finite() && color.z.is_finite()
+    }
+
+    #[test]
+    fn latitude_clamps_ratios_just_past_the_pole() {
+        let over = 1.000_000_1_f32;
+        assert!(over > 1.0);
+        assert_eq!(latitude(over), std::f32::consts::FRAC_PI_2);
+        assert_eq!(latitude(-over), -std::f32::consts::FRAC_PI_2);
+    }
+
+    #[test]
+    fn crater_sizes_stay_in_range() {
+        let sizes = CraterSizes { min_radius: 0.1, max_radius: 0.3, exponent: 1.0 };
+        let craters = generate_craters_with_sizes(7, 200, &sizes);
+        assert!(craters.iter().all(|&(_, r)| (sizes.min_radius..=sizes.max_radius).contains(&r)));
+
+        // Un exponente más alto deja la mayoría de los cráteres más pequeños
+        let mean = |craters: &[(Vector3, f32)]| craters.iter().map(|&(_, r)| r).sum::<f32>() / craters.len() as f32;
+        let skewed = generate_craters_with_sizes(7, 200, &CraterSizes { exponent: 4.0, ..sizes });
+        assert!(mean(&skewed) < mean(&craters));
+    }
+
+    #[test]
+    fn normal_map_wraps_around_in_u() {
+        for v in [0.1, 0.5, 0.8] {
+            let start = sample_normal_map(&Vector2::new(0.0, v), 1.0, NoiseQuality::High, 3);
+            let end = sample_normal_map(&Vector2::new(1.0, v), 1.0, NoiseQuality::High, 3);
+            assert!((start.x - end.x).abs() < 1e-3 && (start.y - end.y).abs() < 1e-3, "{:?} != {:?}", start, end);
+        }
+    }
+
+    #[test]
+    fn poles_have_no_nan_colors() {
+        let lights = [Light::new(Vector3::new(0.0, 0.0, 10.0))];
+        let model = LightingModel::Lambert;
+        for pole in [Vector3::new(0.0, 0.0, 1.0), Vector3::new(0.0, 0.0, -1.0), Vector3::new(1e-7, 0.0, 1.000_000_1)] {
+            let normal = pole;
+            assert!(is_finite(&gaseous_planet_color(&pole, &normal, 0.0, 1.0, model, &lights)));
+            assert!(is_finite(&biolum_planet_color(&pole, &normal, 0.0, 1.0, NoiseQuality::Medium, 0, model, &lights)));
+            assert!(is_finite(&ringed_planet_color(&pole, &normal, 0.0, 1.0, model, &lights)));
+        }
+    }
+}
@@ -0,0 +1,46 @@
+// texture.rs
+use raylib::math::Vector3;
+
+/// An RGBA image kept in main memory for the shaders to sample
+pub struct Texture {
+    pub width: u32,
+    pub height: u32,
+    pixels: Vec<[u8; 4]>, // row 0 is the top of the image
+}
+
+impl Texture {
+    /// Loads a PNG from disk. Any decoding error is returned as an `io::Error`.
+    pub fn load(path: &str) -> Result<Self, std::io::Error> {
+        let image = image::open(path).map_err(std::io::Error::other)?.to_rgba8();
+        let (width, height) = image.dimensions();
+        if width == 0 || height == 0 {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "la imagen no tiene píxeles"));
+        }
+        let pixels = image.pixels().map(|pixel| pixel.0).collect();
+        Ok(Texture { width, height, pixels })
+    }
+
+    // Texel color in [0, 1] with the coordinates wrapped around both edges
+    fn texel(&self, x: i64, y: i64) -> Vector3 {
+        let x = x.rem_euclid(self.width as i64) as usize;
+        let y = y.rem_euclid(self.height as i64) as usize;
+        let [r, g, b, _] = self.pixels[y * self.width as usize + x];
+        Vector3::new(r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0)
+    }
+
+    /// Bilinear sample at texture coordinates (u, v). Coordinates outside [0, 1] wrap, so
+    /// the seam of a sphere blends across the image edges. v follows the OBJ convention
+    /// (0 at the bottom of the image), so the image is not drawn upside down.
+    pub fn sample(&self, u: f32, v: f32) -> Vector3 {
+        // Texel centers sit at half-integer coordinates
+        let x = u * self.width as f32 - 0.5;
+        let y = (1.0 - v) * self.height as f32 - 0.5;
+        let (x0, y0) = (x.floor(), y.floor());
+        let (tx, ty) = (x - x0, y - y0);
+        let (x0, y0) = (x0 as i64, y0 as i64);
+
+        let top = self.texel(x0, y0) * (1.0 - tx) + self.texel(x0 + 1, y0) * tx;
+        let bottom = self.texel(x0, y0 + 1) * (1.0 - tx) + self.texel(x0 + 1, y0 + 1) * tx;
+        top * (1.0 - ty) + bottom * ty
+    }
+}
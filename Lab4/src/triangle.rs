@@ -1,39 +1,122 @@
 use crate::fragment::Fragment;
 use crate::vertex::Vertex;
-use crate::light::Light;
-use raylib::prelude::Vector3;
+use crate::light::{Light, ambient_light};
+use crate::ShadingMode;
+use raylib::prelude::{Vector2, Vector3};
 
-fn barycentric_coordinates(p_x: f32, p_y: f32, a: &Vertex, b: &Vertex, c: &Vertex)  -> (f32, f32, f32) {
-    let a_x = a.transformed_position.x;   
-    let a_y = a.transformed_position.y;
-    let b_x = b.transformed_position.x;
-    let b_y = b.transformed_position.y;
-    let c_x = c.transformed_position.x;
-    let c_y = c.transformed_position.y;
+// Edge function of the screen-space edge a -> b at (p_x, p_y): twice the signed area of (a, b, p)
+fn edge_function(a: &Vector3, b: &Vector3, p_x: f32, p_y: f32) -> f32 {
+    (b.x - a.x) * (p_y - a.y) - (b.y - a.y) * (p_x - a.x)
+}
 
-    let denom = (b_y - c_y) * (a_x - c_x) + (c_x - b_x) * (a_y - c_y);
+// Each weight comes from its own edge (w1 from b -> c, w2 from c -> a, w3 from a -> b)
+// instead of 1 - w1 - w2, so two triangles sharing an edge evaluate it the same way
+fn barycentric_coordinates(p_x: f32, p_y: f32, a: &Vertex, b: &Vertex, c: &Vertex)  -> (f32, f32, f32) {
+    let (a, b, c) = (&a.transformed_position, &b.transformed_position, &c.transformed_position);
 
-    if denom.abs() < 1e-10  {
+    let area = edge_function(a, b, c.x, c.y);
+    if area.abs() < 1e-10  {
         return (-1.0, -1.0, -1.0);
     }
-    
-    let w1 = ((b_y - c_y) * (p_x - c_x) + (c_x - b_x) * (p_y - c_y)) / denom;
-    let w2 = ((c_y - a_y) * (p_x - c_x) + (a_x - c_x) * (p_y - c_y)) / denom;
-    let w3 = 1.0 - w1 - w2;
+
+    let w1 = edge_function(b, c, p_x, p_y) / area;
+    let w2 = edge_function(c, a, p_x, p_y) / area;
+    let w3 = edge_function(a, b, p_x, p_y) / area;
 
     (w1, w2, w3)
 }
 
-pub fn triangle(v1: &Vertex, v2: &Vertex, v3: &Vertex, light: &Light) -> Vec<Fragment> {
-    let mut fragments = Vec::new();
+// Top-left fill rule: a pixel center exactly on an edge (weight 0) belongs to the
+// triangle only if that is a left edge (interior to its right) or a flat top edge
+// (interior below, y grows down). The neighbor across the edge sees the opposite
+// side, so shared edges are drawn exactly once: no gaps, no double blending.
+// The inward normal of a -> b is the gradient of its weight, (-(b.y - a.y), b.x - a.x) / area.
+fn is_top_left(a: &Vector3, b: &Vector3, area: f32) -> bool {
+    let normal_x = -(b.y - a.y) * area.signum();
+    let normal_y = (b.x - a.x) * area.signum();
+    normal_x > 0.0 || (normal_x == 0.0 && normal_y > 0.0)
+}
+
+// Whether a pixel with edge weight `w` is inside that edge under the fill rule
+fn covers(w: f32, top_left: bool) -> bool {
+    w > 0.0 || (w == 0.0 && top_left)
+}
+
+// Barycentric blend of three directions, normalized (zero stays zero)
+fn interpolate_direction(a: &Vector3, b: &Vector3, c: &Vector3, w1: f32, w2: f32, w3: f32) -> Vector3 {
+    let mut v = Vector3::new(
+        w1 * a.x + w2 * b.x + w3 * c.x,
+        w1 * a.y + w2 * b.y + w3 * c.y,
+        w1 * a.z + w2 * b.z + w3 * c.z,
+    );
+    let len = (v.x * v.x + v.y * v.y + v.z * v.z).sqrt();
+    if len > 0.0 {
+        v.x /= len;
+        v.y /= len;
+        v.z /= len;
+    }
+    v
+}
+
+// Diffuse light of all lights at a point, tinted by each light's color, with the same
+// per-channel ambient floor the shaders use
+fn lighting_color(normal: &Vector3, point: &Vector3, lights: &[Light]) -> Vector3 {
+    let mut total = Vector3::new(0.0, 0.0, 0.0);
+    for light in lights {
+        let light_dir = light.direction_to(point);
+        let diffuse = (normal.x * light_dir.x + normal.y * light_dir.y + normal.z * light_dir.z).max(0.0);
+        total += light.color * (diffuse * light.intensity * light.attenuation(point));
+    }
+    let ambient = ambient_light(lights);
+    Vector3::new(total.x.max(ambient), total.y.max(ambient), total.z.max(ambient))
+}
 
+/// Rasterizes a screen-space triangle, handing each covered pixel to `emit` as it is
+/// produced. Only the integer bounding box of the triangle is scanned, and pixels on
+/// shared edges follow the top-left rule so they belong to exactly one triangle.
+/// Every attribute is interpolated with perspective-corrected barycentric weights (only
+/// depth uses the screen-space ones), and they are used differently per mode:
+/// - Procedural, Checker and Textured: they interpolate the normal, world position and texture
+///   coordinates, and lighting is evaluated per fragment
+/// - Gouraud: lighting is evaluated once per vertex and the weights blend the three lit colors
+/// - Flat: one normal for the whole face (the average of the vertex normals) and one lit color;
+///   the weights are only used for depth and world position
+/// - VertexColor: the weights blend the three `Vertex::color`s, no lighting
+pub fn triangle<F: FnMut(Fragment)>(v1: &Vertex, v2: &Vertex, v3: &Vertex, lights: &[Light], shading_mode: ShadingMode, mut emit: F) {
     let base_color = Vector3::new(0.5, 0.5, 0.5);
 
+    // Per-vertex lit colors for Gouraud shading
+    let vertex_colors = [v1, v2, v3].map(|v| base_color * lighting_color(&v.transformed_normal, &v.position, lights));
+
+    // Single lit color for flat shading, evaluated at the centroid
+    let flat_color = {
+        let mut face_normal = v1.transformed_normal + v2.transformed_normal + v3.transformed_normal;
+        let len = (face_normal.x * face_normal.x + face_normal.y * face_normal.y + face_normal.z * face_normal.z).sqrt();
+        if len > 0.0 {
+            face_normal.x /= len;
+            face_normal.y /= len;
+            face_normal.z /= len;
+        }
+        let centroid = (v1.position + v2.position + v3.position) * (1.0 / 3.0);
+        base_color * lighting_color(&face_normal, &centroid, lights)
+    };
+
+    // 1/w at each vertex for perspective-correct interpolation (w is the clip-space w,
+    // i.e. the view depth for the perspective projection and 1.0 for orthographic)
+    let inv_w1 = 1.0 / v1.clip_position.w.max(1e-6);
+    let inv_w2 = 1.0 / v2.clip_position.w.max(1e-6);
+    let inv_w3 = 1.0 / v3.clip_position.w.max(1e-6);
+
     let min_x = v1.transformed_position.x.min(v2.transformed_position.x).min(v3.transformed_position.x).floor() as i32;
     let max_x = v1.transformed_position.x.max(v2.transformed_position.x).max(v3.transformed_position.x).ceil() as i32;
     let min_y = v1.transformed_position.y.min(v2.transformed_position.y).min(v3.transformed_position.y).floor() as i32;
     let max_y = v1.transformed_position.y.max(v2.transformed_position.y).max(v3.transformed_position.y).ceil() as i32;
 
+    // Which edges own the pixel centers lying exactly on them (same order as w1, w2, w3)
+    let (a, b, c) = (&v1.transformed_position, &v2.transformed_position, &v3.transformed_position);
+    let area = edge_function(a, b, c.x, c.y);
+    let top_left = [is_top_left(b, c, area), is_top_left(c, a, area), is_top_left(a, b, area)];
+
     for y in min_y..=max_y {
         for x in min_x..=max_x {
             let p_x = x as f32 + 0.5; //sample at pixel center
@@ -42,13 +125,21 @@ pub fn triangle(v1: &Vertex, v2: &Vertex, v3: &Vertex, light: &Light) -> Vec<Fra
             // Calculate barycentric coordinates
             let (w1, w2, w3) = barycentric_coordinates(p_x, p_y, v1, v2, v3);
 
-            if w1 >= 0.0 && w2 >= 0.0 && w3 >= 0.0 {
+            if covers(w1, top_left[0]) && covers(w2, top_left[1]) && covers(w3, top_left[2]) {
+
+                // Screen-space weights are not linear in world space: divide each weight by the
+                // vertex w, interpolate, and renormalize so attributes don't swim under perspective
+                let p1 = w1 * inv_w1;
+                let p2 = w2 * inv_w2;
+                let p3 = w3 * inv_w3;
+                let p_sum = p1 + p2 + p3;
+                let (p1, p2, p3) = if p_sum > 0.0 { (p1 / p_sum, p2 / p_sum, p3 / p_sum) } else { (w1, w2, w3) };
 
-                // Interpolate normals using barycentric coordinates
+                // Interpolate normals using the perspective-corrected weights
                 let interpolated_normal = Vector3::new(
-                    w1 * v1.transformed_normal.x + w2 * v2.transformed_normal.x + w3 * v3.transformed_normal.x,
-                    w1 * v1.transformed_normal.y + w2 * v2.transformed_normal.y + w3 * v3.transformed_normal.y,
-                    w1 * v1.transformed_normal.z + w2 * v2.transformed_normal.z + w3 * v3.transformed_normal.z,
+                    p1 * v1.transformed_normal.x + p2 * v2.transformed_normal.x + p3 * v3.transformed_normal.x,
+                    p1 * v1.transformed_normal.y + p2 * v2.transformed_normal.y + p3 * v3.transformed_normal.y,
+                    p1 * v1.transformed_normal.z + p2 * v2.transformed_normal.z + p3 * v3.transformed_normal.z,
                 );
 
                 // Normalize the interpolated normal
@@ -60,45 +151,140 @@ pub fn triangle(v1: &Vertex, v2: &Vertex, v3: &Vertex, light: &Light) -> Vec<Fra
                     normalized_normal.y /= normal_length;
                     normalized_normal.z /= normal_length;
                 }
-                
+
                 // Calculate position in world space for this fragment
                 let world_pos = Vector3::new(
-                    w1 * v1.position.x + w2 * v2.position.x + w3 * v3.position.x,
-                    w1 * v1.position.y + w2 * v2.position.y + w3 * v3.position.y,
-                    w1 * v1.position.z + w2 * v2.position.z + w3 * v3.position.z,
+                    p1 * v1.position.x + p2 * v2.position.x + p3 * v3.position.x,
+                    p1 * v1.position.y + p2 * v2.position.y + p3 * v3.position.y,
+                    p1 * v1.position.z + p2 * v2.position.z + p3 * v3.position.z,
                 );
 
-                // Light direction (from surface to light) for this fragment
-                let mut light_dir = Vector3::new(
-                    light.position.x - world_pos.x,
-                    light.position.y - world_pos.y,
-                    light.position.z - world_pos.z,
+                let tex_coords = Vector2::new(
+                    p1 * v1.tex_coords.x + p2 * v2.tex_coords.x + p3 * v3.tex_coords.x,
+                    p1 * v1.tex_coords.y + p2 * v2.tex_coords.y + p3 * v3.tex_coords.y,
                 );
 
-                // Normalize light direction
-                let light_length = (light_dir.x * light_dir.x + light_dir.y * light_dir.y + light_dir.z * light_dir.z).sqrt();
-                if light_length > 0.0 {
-                    light_dir.x /= light_length;
-                    light_dir.y /= light_length;
-                    light_dir.z /= light_length;
-                }
-
-                // Calculate per-fragment lighting intensity using interpolated normal and light direction
-                let intensity = (normalized_normal.x * light_dir.x + normalized_normal.y * light_dir.y + normalized_normal.z * light_dir.z).max(0.0);
+                let shaded_color = match shading_mode {
+                    ShadingMode::Procedural | ShadingMode::Checker | ShadingMode::Textured => base_color * lighting_color(&normalized_normal, &world_pos, lights),
+                    ShadingMode::Gouraud => vertex_colors[0] * p1 + vertex_colors[1] * p2 + vertex_colors[2] * p3,
+                    ShadingMode::Flat => flat_color,
+                    ShadingMode::VertexColor => v1.color * p1 + v2.color * p2 + v3.color * p3,
+                };
 
-                let shaded_color = Vector3::new(
-                    base_color.x * intensity,
-                    base_color.y * intensity,
-                    base_color.z * intensity,
-                );
-
-                // Interpolate depth using barycentric coordinates
+                // Depth (z after the perspective divide) is linear in screen space, so it keeps the plain weights
                 let depth = w1 * v1.transformed_position.z + w2 * v2.transformed_position.z + w3 * v3.transformed_position.z;
 
-                fragments.push(Fragment::new(p_x, p_y, shaded_color, depth, world_pos));
+                // Tangent frame, interpolated like the normal, for normal mapping
+                let tangent = interpolate_direction(&v1.transformed_tangent, &v2.transformed_tangent, &v3.transformed_tangent, p1, p2, p3);
+                let bitangent = interpolate_direction(&v1.transformed_bitangent, &v2.transformed_bitangent, &v3.transformed_bitangent, p1, p2, p3);
+
+                emit(Fragment::new(p_x, p_y, shaded_color, depth, world_pos, tex_coords, normalized_normal)
+                    .with_tangent_frame(tangent, bitangent));
             }
         }
     }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use raylib::prelude::Vector4;
+
+    // A simple perspective camera at the origin looking down -Z: w = -z and a
+    // 100x100 viewport with 40 pixels per unit at distance 1
+    fn project(position: Vector3) -> Vertex {
+        let w = -position.z;
+        let mut vertex = Vertex::new(position, Vector3::new(0.0, 0.0, 1.0), Vector2::new(0.0, 0.0));
+        vertex.transformed_position = Vector3::new(50.0 + 40.0 * position.x / w, 50.0 - 40.0 * position.y / w, 1.0 - 1.0 / w);
+        vertex.clip_position = Vector4::new(position.x, position.y, position.z, w);
+        vertex
+    }
+
+    fn collect(v1: &Vertex, v2: &Vertex, v3: &Vertex, shading_mode: ShadingMode) -> Vec<Fragment> {
+        let mut fragments = Vec::new();
+        triangle(v1, v2, v3, &[], shading_mode, |fragment| fragments.push(fragment));
+        fragments
+    }
+
+    #[test]
+    fn steep_triangle_world_position_is_perspective_correct() {
+        // Near edge at z = -2, far tip at z = -20: a floor receding from the camera
+        let a = Vector3::new(-1.0, -1.0, -2.0);
+        let b = Vector3::new(1.0, -1.0, -2.0);
+        let c = Vector3::new(0.0, -1.0, -20.0);
+        let fragments = collect(&project(a), &project(b), &project(c), ShadingMode::Procedural);
+
+        // The pixel holding the projection of the midpoint of the median from c
+        let midpoint = Vector3::new((a.x + b.x) * 0.25 + c.x * 0.5, (a.y + b.y) * 0.25 + c.y * 0.5, (a.z + b.z) * 0.25 + c.z * 0.5);
+        let target = project(midpoint).transformed_position;
+        let fragment = fragments
+            .iter()
+            .find(|f| f.position.x.floor() == target.x.floor() && f.position.y.floor() == target.y.floor())
+            .expect("the midpoint is inside the triangle");
+
+        // Analytic answer: the ray through that pixel center hits the plane y = -1 at t = 1 / ray.y
+        let ray = Vector3::new((fragment.position.x - 50.0) / 40.0, (50.0 - fragment.position.y) / 40.0, -1.0);
+        let t = -1.0 / ray.y;
+        let expected = Vector3::new(ray.x * t, -1.0, -t);
+
+        let p = fragment.world_position;
+        assert!((p.x - expected.x).abs() < 1e-3 && (p.y - expected.y).abs() < 1e-3 && (p.z - expected.z).abs() < 1e-3,
+            "{:?} != {:?}", p, expected);
+    }
+
+    fn screen_vertex(x: f32, y: f32) -> Vertex {
+        let mut vertex = Vertex::new(Vector3::new(x, y, 0.0), Vector3::new(0.0, 0.0, 1.0), Vector2::new(0.0, 0.0));
+        vertex.transformed_position = Vector3::new(x, y, 0.5);
+        vertex
+    }
 
-    fragments
-}
\ No newline at end of file
+    // Expected pixels worked out by hand from where each row of pixel centers crosses the edges
+    #[test]
+    fn reference_triangle_coverage() {
+        let fragments = collect(&screen_vertex(1.0, 1.0), &screen_vertex(7.0, 2.5), &screen_vertex(2.5, 7.0), ShadingMode::Flat);
+        let mut mask = [['.'; 8]; 8];
+        for fragment in &fragments {
+            mask[fragment.position.y as usize][fragment.position.x as usize] = '#';
+        }
+        let rows: Vec<String> = mask.iter().map(|row| row.iter().collect()).collect();
+        assert_eq!(rows, [
+            "........",
+            ".##.....",
+            ".######.",
+            "..####..",
+            "..###...",
+            "..##....",
+            "..#.....",
+            "........",
+        ]);
+        assert_eq!(fragments.len(), rows.iter().map(|row| row.matches('#').count()).sum::<usize>());
+    }
+
+    #[test]
+    fn shared_edge_has_no_seam() {
+        use crate::framebuffer::Framebuffer;
+        use raylib::prelude::Color;
+
+        // A square split along its diagonal, which runs exactly through pixel centers
+        let (a, b, c, d) = (screen_vertex(1.0, 1.0), screen_vertex(9.0, 1.0), screen_vertex(9.0, 9.0), screen_vertex(1.0, 9.0));
+        let mut framebuffer = Framebuffer::new(10, 10);
+        framebuffer.clear();
+        let mut hits = [[0; 10]; 10];
+        for (v1, v2, v3) in [(&a, &b, &c), (&a, &c, &d)] {
+            for fragment in collect(v1, v2, v3, ShadingMode::Flat) {
+                let (x, y) = (fragment.position.x as i32, fragment.position.y as i32);
+                hits[y as usize][x as usize] += 1;
+                framebuffer.point(x, y, Vector3::new(1.0, 1.0, 1.0), fragment.depth);
+            }
+        }
+
+        for i in 1..9 {
+            assert_ne!(framebuffer.get_pixel(i, i), Some(Color::BLACK), "seam at ({}, {})", i, i);
+        }
+        for (y, row) in hits.iter().enumerate() {
+            for (x, count) in row.iter().enumerate() {
+                let inside = (1..9).contains(&x) && (1..9).contains(&y);
+                assert_eq!(*count, inside as i32, "pixel ({}, {}) drawn {} times", x, y, count);
+            }
+        }
+    }
+}
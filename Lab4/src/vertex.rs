@@ -1,7 +1,7 @@
 //vertex.rs
 #![allow(dead_code)]
 
-use raylib::math::{Vector2, Vector3};
+use raylib::math::{Vector2, Vector3, Vector4};
 
 #[derive(Clone, Debug)]
 pub struct Vertex {
@@ -11,6 +11,11 @@ pub struct Vertex {
   pub color: Vector3,
   pub transformed_position: Vector3,
   pub transformed_normal: Vector3,
+  pub tangent: Vector3,   // along +u of the texture coordinates
+  pub bitangent: Vector3, // along +v of the texture coordinates
+  pub transformed_tangent: Vector3,
+  pub transformed_bitangent: Vector3,
+  pub clip_position: Vector4, // before the perspective divide, used for near-plane clipping
 }
 
 impl Vertex {
@@ -22,6 +27,11 @@ impl Vertex {
       color: Vector3::new(0.0, 0.0, 0.0), // Black
       transformed_position: position,
       transformed_normal: normal,
+      tangent: Vector3::new(0.0, 0.0, 0.0),
+      bitangent: Vector3::new(0.0, 0.0, 0.0),
+      transformed_tangent: Vector3::new(0.0, 0.0, 0.0),
+      transformed_bitangent: Vector3::new(0.0, 0.0, 0.0),
+      clip_position: Vector4::new(position.x, position.y, position.z, 1.0),
     }
   }
 
@@ -33,6 +43,11 @@ impl Vertex {
       color,
       transformed_position: Vector3::new(0.0, 0.0, 0.0),
       transformed_normal: Vector3::new(0.0, 0.0, 0.0),
+      tangent: Vector3::new(0.0, 0.0, 0.0),
+      bitangent: Vector3::new(0.0, 0.0, 0.0),
+      transformed_tangent: Vector3::new(0.0, 0.0, 0.0),
+      transformed_bitangent: Vector3::new(0.0, 0.0, 0.0),
+      clip_position: Vector4::new(position.x, position.y, position.z, 1.0),
     }
   }
 
@@ -51,6 +66,11 @@ impl Default for Vertex {
       color: Vector3::new(0.0, 0.0, 0.0), // Black
       transformed_position: Vector3::new(0.0, 0.0, 0.0),
       transformed_normal: Vector3::new(0.0, 1.0, 0.0),
+      tangent: Vector3::new(1.0, 0.0, 0.0),
+      bitangent: Vector3::new(0.0, 0.0, -1.0),
+      transformed_tangent: Vector3::new(1.0, 0.0, 0.0),
+      transformed_bitangent: Vector3::new(0.0, 0.0, -1.0),
+      clip_position: Vector4::new(0.0, 0.0, 0.0, 1.0),
     }
   }
 }
\ No newline at end of file